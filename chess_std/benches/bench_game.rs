@@ -33,6 +33,37 @@ fn bench_game(b: &mut Bencher) {
     });
 }
 
+#[bench]
+fn bench_get_result(b: &mut Bencher) {
+    b.iter(|| {
+        let mut i = 0;
+        let mut board = Board::new();
+        while board.get_result() == GameResult::NoResult {
+            i += 1;
+            let mv = board.legal_moves()
+                .nth(i % board.num_moves())
+                .unwrap();
+            board = board.play_move(mv);
+        }
+    });
+}
+
+#[bench]
+fn bench_result_from_moves(b: &mut Bencher) {
+    b.iter(|| {
+        let mut i = 0;
+        let mut board = Board::new();
+        let mut gen = board.legal_moves();
+        while board.result_from_moves(&gen) == GameResult::NoResult {
+            i += 1;
+            let n = gen.len();
+            let mv = gen.nth(i % n).unwrap();
+            board = board.play_move(mv);
+            gen = board.legal_moves();
+        }
+    });
+}
+
 #[bench]
 fn bench_board_until_over(b: &mut Bencher) {
     let mut num_iterations = 0;
@@ -50,4 +81,23 @@ fn bench_board_until_over(b: &mut Bencher) {
             board = board.play_move(mv);
         }
     });
-}
\ No newline at end of file
+}
+
+#[bench]
+fn bench_san_game(b: &mut Bencher) {
+    // A full game, where every legal move of each position is written.
+    let mut game = Game::new();
+    let mut i = 0;
+    while !game.is_finished() && !game.can_claim_draw() {
+        i += 1;
+        let mv = game.legal_moves()
+            .nth(i % game.board().num_moves())
+            .unwrap();
+        game.play_move(mv);
+    }
+    b.iter(|| {
+        game.boards.iter()
+            .flat_map(|board| board.legal_moves().map(move |mv| board.to_san(mv)))
+            .count()
+    });
+}
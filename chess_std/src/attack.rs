@@ -62,6 +62,86 @@ pub fn fill_line(from: Square, to: Square) -> Bitboard {
     }
 }
 
+impl Direction {
+    /// The direction from a square to another, or `NoDir` when they
+    /// are the same or not on a common rank, file or diagonal.
+    /// 
+    /// ```
+    /// use chess_std::{Square, Direction};
+    /// 
+    /// assert_eq!(Direction::from_squares(Square::E4, Square::B7), Direction::NorthWest);
+    /// assert_eq!(Direction::from_squares(Square::E4, Square::B6), Direction::NoDir);
+    /// ```
+    #[inline]
+    pub fn from_squares(from: Square, to: Square) -> Self {
+        direction_between(from, to)
+    }
+}
+
+/// Alignment queries, for pins and skewers.
+impl Square {
+    /// Whether two different squares are on the same rank, file or diagonal.
+    /// 
+    /// ```
+    /// use chess_std::Square;
+    /// 
+    /// assert!(Square::A1.is_aligned(Square::H8));
+    /// assert!(Square::E2.is_aligned(Square::E7));
+    /// assert!(!Square::G1.is_aligned(Square::F3));
+    /// assert!(!Square::E4.is_aligned(Square::E4));
+    /// ```
+    #[inline]
+    pub fn is_aligned(self, other: Square) -> bool {
+        direction_between(self, other) != NoDir
+    }
+
+    /// Whether two different squares are on the same diagonal.
+    /// 
+    /// ```
+    /// use chess_std::Square;
+    /// 
+    /// assert!(Square::H1.same_diagonal(Square::C6));
+    /// assert!(!Square::H1.same_diagonal(Square::H6));
+    /// assert!(!Square::B1.same_diagonal(Square::C5));
+    /// ```
+    #[inline]
+    pub fn same_diagonal(self, other: Square) -> bool {
+        direction_between(self, other).is_diagonal()
+    }
+
+    /// Whether two different squares are on the same rank or file.
+    /// 
+    /// ```
+    /// use chess_std::Square;
+    /// 
+    /// assert!(Square::H1.same_line(Square::H6));
+    /// assert!(Square::A4.same_line(Square::G4));
+    /// assert!(!Square::H1.same_line(Square::C6));
+    /// ```
+    #[inline]
+    pub fn same_line(self, other: Square) -> bool {
+        direction_between(self, other).is_orthogonal()
+    }
+
+    /// The number of king moves between two squares, read from a table
+    /// rather than computed as `Square::distance`.
+    /// 
+    /// ```
+    /// use chess_std::Square;
+    /// 
+    /// assert_eq!(Square::king_distance_table(Square::B2, Square::G8), 6);
+    /// assert_eq!(Square::E4.king_distance_table(Square::E4), 0);
+    /// ```
+    #[inline]
+    pub fn king_distance_table(self, other: Square) -> u8 {
+        unsafe {
+            *KING_DISTANCE
+                .get_unchecked(self.index())
+                .get_unchecked(other.index())
+        }
+    }
+}
+
 /// "fill" a ray attack towards a direction. The ray will be blocked by
 /// the first blocker if any, but also include the blocker square
 /// if it is an enemy.
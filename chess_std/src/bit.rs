@@ -56,6 +56,38 @@ pub const fn single(sq: Square) -> Bitboard {
     Bitboard(1u64 << (sq.0 as u64))
 }
 
+/// The squares strictly between two squares, computed by walking from the
+/// first one to the second one. This is empty when they are not on
+/// a common rank, file or diagonal.
+/// 
+/// `attack::fill_between` is faster, but needs the generated tables.
+/// 
+/// ```
+/// use chess_std::{bit::{self, single}, Square};
+/// 
+/// assert_eq!(bit::between(Square::B2, Square::E5), single(Square::C3) | single(Square::D4));
+/// assert_eq!(bit::between(Square::B2, Square::E4), bit::EMPTY);
+/// ```
+pub fn between(a: Square, b: Square) -> Bitboard {
+    let d_file = b.file().0 as i8 - a.file().0 as i8;
+    let d_rank = b.rank().0 as i8 - a.rank().0 as i8;
+    let aligned = d_file == 0 || d_rank == 0 || d_file.abs() == d_rank.abs();
+    let mut bb = EMPTY;
+    if a == b || !aligned {
+        return bb;
+    }
+    let (step_file, step_rank) = (d_file.signum(), d_rank.signum());
+    let mut sq = a;
+    while let Some(next) = sq.offset(step_file, step_rank) {
+        if next == b {
+            break;
+        }
+        bb.add(next);
+        sq = next;
+    }
+    bb
+}
+
 
 impl Bitboard {
     /// The index of ls1b. Returns `Square(64)` on empty sets.
@@ -86,6 +118,59 @@ impl Bitboard {
         }
     }
 
+    /// The index of ls1b, or `None` on empty sets.
+    /// ```
+    /// use chess_std::{Square, bit, Bitboard};
+    /// assert_eq!(Bitboard(0b0000_1000).try_scan_forward(), Some(Square::D1));
+    /// assert_eq!(bit::EMPTY.try_scan_forward(), None);
+    /// ```
+    #[inline(always)]
+    pub fn try_scan_forward(self) -> Option<Square> {
+        if self.is_populated() {
+            Some(self.scan_forward())
+        } else {
+            None
+        }
+    }
+
+    /// The index of ms1b, or `None` on empty sets.
+    /// ```
+    /// use chess_std::{Square, bit, Bitboard};
+    /// assert_eq!(Bitboard(0b1000_0000).try_scan_reverse(), Some(Square::H1));
+    /// assert_eq!(bit::EMPTY.try_scan_reverse(), None);
+    /// ```
+    #[inline(always)]
+    pub fn try_scan_reverse(self) -> Option<Square> {
+        if self.is_populated() {
+            Some(self.scan_reverse())
+        } else {
+            None
+        }
+    }
+
+    /// The first square of the set, or `None` on empty sets.
+    /// This is the safe variant of `Bitboard::scan_forward`.
+    /// ```
+    /// use chess_std::{Square, bit};
+    /// assert_eq!(bit::RANK_2.first(), Some(Square::A2));
+    /// assert_eq!(bit::EMPTY.first(), None);
+    /// ```
+    #[inline]
+    pub fn first(self) -> Option<Square> {
+        self.try_scan_forward()
+    }
+
+    /// The squares of the set, from A1 to H8.
+    /// ```
+    /// use chess_std::{Square, bit::{self, single}};
+    /// let bb = single(Square::H8) | single(Square::C2);
+    /// assert_eq!(bb.squares(), vec![Square::C2, Square::H8]);
+    /// assert!(bit::EMPTY.squares().is_empty());
+    /// ```
+    pub fn squares(self) -> Vec<Square> {
+        self.collect()
+    }
+
     /// Returns whether the set is populated (non-zero).
     #[inline(always)]
     pub fn is_populated(self) -> bool {
@@ -184,6 +269,66 @@ impl Bitboard {
         Self(bb)
     }
 
+    /// Shift the set a number of times in a direction.
+    /// The squares leaving the board are lost.
+    /// 
+    /// ```
+    /// use chess_std::{Square, Direction::*, bit::{self, single}};
+    /// 
+    /// assert_eq!(single(Square::B1).shift_times(NorthEast, 3), single(Square::E4));
+    /// assert_eq!(bit::RANK_2.shift_times(North, 6), bit::RANK_8);
+    /// assert_eq!(bit::RANK_2.shift_times(North, 7), bit::EMPTY);
+    /// ```
+    #[inline]
+    pub fn shift_times(self, dir: Direction, n: u32) -> Self {
+        (0..n).fold(self, |bb, _| bb.shift(dir))
+    }
+
+    /// Extends every square of the set towards the 8th rank.
+    /// 
+    /// ```
+    /// use chess_std::{Square, bit::{self, single}};
+    /// 
+    /// assert_eq!(single(Square::C1).north_fill(), bit::FILE_C);
+    /// ```
+    #[inline]
+    pub fn north_fill(self) -> Self {
+        let mut bb = self.0;
+        bb |= bb <<  8;
+        bb |= bb << 16;
+        bb |= bb << 32;
+        Self(bb)
+    }
+
+    /// Extends every square of the set towards the 1st rank.
+    /// 
+    /// ```
+    /// use chess_std::{Square, bit::{self, single}};
+    /// 
+    /// assert_eq!(single(Square::H8).south_fill(), bit::FILE_H);
+    /// ```
+    #[inline]
+    pub fn south_fill(self) -> Self {
+        let mut bb = self.0;
+        bb |= bb >>  8;
+        bb |= bb >> 16;
+        bb |= bb >> 32;
+        Self(bb)
+    }
+
+    /// The files that contain at least one square of the set.
+    /// 
+    /// ```
+    /// use chess_std::{Square, bit::{self, single}};
+    /// 
+    /// assert_eq!((single(Square::A4) | single(Square::A6) | single(Square::E2)).file_fill(),
+    ///            bit::FILE_A | bit::FILE_E);
+    /// ```
+    #[inline]
+    pub fn file_fill(self) -> Self {
+        self.north_fill() | self.south_fill()
+    }
+
     /// Returns the number of squares in the set (equivalent to the number of ones
     /// in the binary representation).
     #[inline(always)]
@@ -280,6 +425,22 @@ macro_rules! merge_sq {
 
 
 
+#[test]
+fn test_between() {
+    use crate::attack::fill_between;
+    assert_eq!(between(Square::H1, Square::A8),
+               DIAG_A8_H1 ^ single(Square::A8) ^ single(Square::H1));
+    assert_eq!(between(Square::A8, Square::H1), between(Square::H1, Square::A8));
+    assert_eq!(between(Square::C3, Square::D4), EMPTY);
+    assert_eq!(between(Square::C3, Square::C3), EMPTY);
+    for a in Square::A1..=Square::H8 {
+        for b in Square::A1..=Square::H8 {
+            let expected = if a.is_aligned(b) { fill_between(a, b) } else { EMPTY };
+            assert_eq!(between(a, b), expected, "{} {}", a, b);
+        }
+    }
+}
+
 #[test]
 fn test_iter() {
     use crate::units::{Rank, File};
@@ -105,10 +105,11 @@ impl Builder {
             half_move_clock: self.half_move_clock,
             ep_target: None,
             rights: self.rights,
-            last_cap_or_push: self.half_move_clock * 2,
+            fullmove_number: 1,
 
             checkers: bit::EMPTY,
             pinned: bit::EMPTY,
+            pinners: bit::EMPTY,
         };
         if !board.is_valid() {
             return None;
@@ -0,0 +1,168 @@
+/* Classification of openings by their ECO code (Encyclopaedia of Chess
+   Openings), matching the first moves of a game against a table.
+*/
+
+use crate::moves::Move;
+use crate::position::Board;
+use lazy_static::lazy_static;
+
+
+// The code, the name and the SAN moves of each opening, from the
+// standard start, one opening per line of `eco.tsv`.
+lazy_static! {
+    static ref ECO_OPENINGS: Vec<(&'static str, &'static str, &'static str)> =
+        include_str!("eco.tsv").lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                let mut field = || fields.next().expect("eco.tsv: missing field").trim();
+                (field(), field(), field())
+            })
+            .collect();
+}
+
+/// The ECO code and the name of the opening of a game, given its moves
+/// from the start board. The opening whose moves are the longest prefix
+/// of the game is selected, and `None` is returned when no opening matches.
+///
+/// The openings of the table are played from the standard start,
+/// which should then be `start` for the codes to make sense.
+///
+/// ```
+/// use chess_std::{Board, Game, classify_eco};
+///
+/// let game = Game::from_pgn("1. d4 Nf6 2. c4 g6 3. Nc3 Bg7").unwrap();
+/// let (code, name) = classify_eco(&game.moves, &Board::new()).unwrap();
+/// assert_eq!(code, "E61");
+/// assert_eq!(name, "King's Indian Defence");
+/// ```
+pub fn classify_eco(moves: &[Move], start: &Board) -> Option<(String, String)> {
+    classify_with(&ECO_OPENINGS, moves, start)
+}
+
+// The longest opening of `openings` which is a prefix of the moves.
+fn classify_with(openings: &[(&str, &str, &str)], moves: &[Move], start: &Board)
+    -> Option<(String, String)>
+{
+    let max_plies = openings.iter()
+        .map(|(_, _, line)| line.split_whitespace().count())
+        .max()
+        .unwrap_or(0);
+
+    // The SAN of the first moves, without check suffixes.
+    let mut board = start.clone();
+    let mut played = Vec::with_capacity(max_plies);
+    for &mv in moves.iter().take(max_plies) {
+        if !board.is_move_legal(mv) {
+            break;
+        }
+        played.push(board.to_san(mv).trim_end_matches(['+', '#']).to_owned());
+        board = board.play_move(mv);
+    }
+
+    openings.iter()
+        .filter_map(|(code, name, line)| {
+            let line: Vec<_> = line.split_whitespace().collect();
+            let matches = line.len() <= played.len() &&
+                          line.iter().zip(&played).all(|(a, b)| a == b);
+            if matches { Some((line.len(), code, name)) } else { None }
+        })
+        .max_by_key(|(len, _, _)| *len)
+        .map(|(_, code, name)| (code.to_string(), name.to_string()))
+}
+
+
+#[cfg(test)]
+mod eco_test {
+    use super::*;
+    use crate::Game;
+
+    // A subset of the table, small enough for the tests to pin down
+    // which line is selected.
+    const OPENINGS: &[(&str, &str, &str)] = &[
+        ("A04", "Reti Opening", "Nf3"),
+        ("A10", "English Opening", "c4"),
+        ("A40", "Queen's Pawn Game", "d4"),
+        ("A45", "Indian Defence", "d4 Nf6"),
+        ("A80", "Dutch Defence", "d4 f5"),
+        ("B00", "King's Pawn Opening", "e4"),
+        ("B01", "Scandinavian Defence", "e4 d5"),
+        ("B07", "Pirc Defence", "e4 d6 d4 Nf6"),
+        ("B10", "Caro-Kann Defence", "e4 c6"),
+        ("B20", "Sicilian Defence", "e4 c5"),
+        ("B30", "Sicilian Defence", "e4 c5 Nf3 Nc6"),
+        ("B50", "Sicilian Defence", "e4 c5 Nf3 d6"),
+        ("B90", "Sicilian Defence, Najdorf Variation",
+         "e4 c5 Nf3 d6 d4 cxd4 Nxd4 Nf6 Nc3 a6"),
+        ("C00", "French Defence", "e4 e6"),
+        ("C20", "King's Pawn Game", "e4 e5"),
+        ("C30", "King's Gambit", "e4 e5 f4"),
+        ("C40", "King's Knight Opening", "e4 e5 Nf3"),
+        ("C42", "Petrov's Defence", "e4 e5 Nf3 Nf6"),
+        ("C44", "King's Pawn Game", "e4 e5 Nf3 Nc6"),
+        ("C45", "Scotch Game", "e4 e5 Nf3 Nc6 d4"),
+        ("C50", "Italian Game", "e4 e5 Nf3 Nc6 Bc4"),
+        ("C60", "Ruy Lopez", "e4 e5 Nf3 Nc6 Bb5"),
+        ("C65", "Ruy Lopez, Berlin Defence", "e4 e5 Nf3 Nc6 Bb5 Nf6"),
+        ("C68", "Ruy Lopez, Exchange Variation", "e4 e5 Nf3 Nc6 Bb5 a6 Bxc6"),
+        ("C70", "Ruy Lopez", "e4 e5 Nf3 Nc6 Bb5 a6 Ba4"),
+        ("D00", "Queen's Pawn Game", "d4 d5"),
+        ("D06", "Queen's Gambit", "d4 d5 c4"),
+        ("D20", "Queen's Gambit Accepted", "d4 d5 c4 dxc4"),
+        ("D30", "Queen's Gambit Declined", "d4 d5 c4 e6"),
+        ("E60", "King's Indian Defence", "d4 Nf6 c4 g6"),
+    ];
+
+    fn classify(pgn: &str) -> Option<(String, String)> {
+        let game = Game::from_pgn(pgn).unwrap();
+        classify_with(OPENINGS, &game.moves, &Board::new())
+    }
+
+    #[test]
+    fn test_ruy_lopez() {
+        let (code, name) = classify("1. e4 e5 2. Nf3 Nc6 3. Bb5 a6").unwrap();
+        assert_eq!((code.as_str(), name.as_str()), ("C60", "Ruy Lopez"));
+        let (code, _) = classify("1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O").unwrap();
+        assert_eq!(code, "C70");
+        let (code, _) = classify("1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 4. Bxc6 dxc6").unwrap();
+        assert_eq!(code, "C68");
+    }
+
+    #[test]
+    fn test_longest_prefix() {
+        let najdorf = "1. e4 c5 2. Nf3 d6 3. d4 cxd4 4. Nxd4 Nf6 5. Nc3 a6 6. Be3";
+        assert_eq!(classify(najdorf).unwrap().0, "B90");
+        // One move short of the Najdorf.
+        assert_eq!(classify("1. e4 c5 2. Nf3 d6 3. d4 cxd4 4. Nxd4 Nf6 5. Nc3").unwrap().0, "B50");
+        assert_eq!(classify("1. b3 e5"), None);
+        assert_eq!(classify_eco(&[], &Board::new()), None);
+    }
+
+    #[test]
+    fn test_full_table() {
+        for (code, name, line) in ECO_OPENINGS.iter() {
+            let mut game = Game::new();
+            for san in line.split_whitespace() {
+                let mv = game.parse_move(san)
+                    .unwrap_or_else(|e| panic!("{} {}: {}: {}", code, name, san, e));
+                assert_eq!(game.board().to_san(mv).trim_end_matches(['+', '#']),
+                           san.trim_end_matches(['+', '#']), "{} {}", code, name);
+                game.play_move(mv);
+            }
+        }
+        for volume in ['A', 'B', 'C', 'D', 'E'] {
+            for n in 0..100 {
+                let code = format!("{}{:02}", volume, n);
+                assert!(ECO_OPENINGS.iter().any(|(c, _, _)| *c == code), "{}", code);
+            }
+        }
+    }
+
+    #[test]
+    fn test_italian() {
+        let italian = |pgn| classify_eco(&Game::from_pgn(pgn).unwrap().moves, &Board::new());
+        assert_eq!(italian("1. e4 e5 2. Nf3 Nc6 3. Bc4 Nf6").unwrap().0, "C55");
+        assert_eq!(italian("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. c3").unwrap().0, "C53");
+        assert_eq!(italian("1. e4 e5 2. Nf3 Nc6 3. Bc4 Be7").unwrap().0, "C50");
+    }
+}
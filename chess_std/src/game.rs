@@ -7,21 +7,39 @@ use {regex::Regex, lazy_static::lazy_static, derive_more::Index};
 #[cfg(feature = "pgn")]
 use std::{convert::TryFrom, collections::HashMap};
 #[cfg(feature = "trees")]
-use std::{rc::Rc, cell::RefCell};
+use std::{rc::{Rc, Weak}, cell::{Ref, RefCell}};
 
 use crate::prelude::*;
 use crate::position::{Board, zobrist};
 use crate::movegen::{MoveGen, MoveGenMasked};
+#[cfg(feature = "pgn")]
+use crate::pgn::{PGNTokenizer, PGNToken, Variation};
 
 
 /// A stack of boards and moves, where the last element is the current one.
 /// 
 /// For performance, this approach is less efficient than simply using `Board` objects.
 /// It also duplicates some `Board` methods, for convenience.
+/// 
+/// Cloning a game copies the whole stack, to explore another line from it:
+/// ```
+/// use chess_std::prelude::*;
+/// use chess_std::{Game, Board};
+/// 
+/// let game = Game::new();
+/// let mut line = game.clone();
+/// line.play_move(Move::quiet(Square::E2, Square::E4));
+/// assert_eq!(line.moves.len(), 1);
+/// assert!(game.moves.is_empty());
+/// assert_eq!(game.board(), &Board::new());
+/// ```
+#[derive(Clone)]
 pub struct Game {
     pub boards: Vec<Board>,
     pub moves: Moves,
     hashes: Vec<zobrist::Hash>,
+    last_irreversible: usize,                    // Ply after the last irreversible move
+    redo: Moves,                                 // Undone moves, the next one last
     
     pub result: GameResult
 }
@@ -55,6 +73,8 @@ impl Game {
             boards,
             moves: Self::vec_default(), 
             hashes,
+            last_irreversible: 0,
+            redo: Vec::new(),
             result: GameResult::NoResult
         }
     }
@@ -68,10 +88,44 @@ impl Game {
             boards,
             moves: Self::vec_default(), 
             hashes,
+            last_irreversible: 0,
+            redo: Vec::new(),
             result: GameResult::NoResult
         }
     }
 
+    /// A game from a start board and a list of moves, each one being checked.
+    /// Returns the index of the first illegal move and the reason otherwise.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::{Game, Board};
+    /// 
+    /// let moves = [
+    ///     Move::quiet(Square::E2, Square::E4),
+    ///     Move::quiet(Square::E7, Square::E5),
+    ///     Move::quiet(Square::E4, Square::E5), // Blocked
+    ///     Move::quiet(Square::G1, Square::F3),
+    /// ];
+    /// let game = Game::replay(Board::new(), &moves[..2]).unwrap();
+    /// assert_eq!(game.moves, &moves[..2]);
+    /// let err = Game::replay(Board::new(), &moves).err();
+    /// assert_eq!(err.map(|(index, _)| index), Some(2));
+    /// ```
+    pub fn replay(start: Board, moves: &[Move]) -> Result<Game, (usize, String)> {
+        let mut game = Game::from_board(start);
+        for (i, &mv) in moves.iter().enumerate() {
+            if game.is_finished() {
+                return Err((i, "The game is already finished".to_owned()));
+            }
+            if !game.is_move_legal(mv) {
+                return Err((i, format!("Illegal move: {}", mv)));
+            }
+            game.play_move(mv);
+        }
+        Ok(game)
+    }
+
     // The current board, on top of the stack.
     pub fn board(&self) -> &Board {
         self.boards.last().unwrap()
@@ -82,6 +136,27 @@ impl Game {
         self.boards.last_mut().unwrap()
     }
 
+    /// The board after a number of half-moves, the first board being at 0.
+    /// Returns None when the game is shorter.
+    ///
+    /// ```
+    /// use chess_std::{Game, Board, Square, Move};
+    ///
+    /// let mut game = Game::new();
+    /// game.play_move(Move::quiet(Square::E2, Square::E4));
+    /// assert_eq!(game.position_after(0), Some(&Board::new()));
+    /// assert_eq!(game.position_after(1), Some(game.board()));
+    /// assert_eq!(game.position_after(2), None);
+    /// ```
+    pub fn position_after(&self, ply: usize) -> Option<&Board> {
+        self.boards.get(ply)
+    }
+
+    /// The FEN of every board in the game, starting with the first one.
+    pub fn fen_history(&self) -> Vec<String> {
+        self.boards.iter().map(Board::to_fen).collect()
+    }
+
 
     /// See: `Board::legal_moves_from`.
     pub fn legal_moves_from(&self, sq: Square) -> MoveGenMasked {
@@ -145,24 +220,168 @@ impl Game {
     /// assert!(game.in_checkmate());
     /// ```
     pub fn play_move(&mut self, mv: Move) -> &Self {
+        self.redo.clear();
+        self.push_move(mv)
+    }
+
+    // Play a move, keeping the undone moves.
+    fn push_move(&mut self, mv: Move) -> &Self {
         assert!(!self.is_finished(), "Playing move when game is finished");
-        self.hashes.push(self.board().zobrist_hash());
         self.boards.push(self.board().play_move(mv));
+        self.hashes.push(self.board().zobrist_hash());
         self.moves.push(mv);
-        if self.is_finished() {
-            self.result = self.board().get_result();
+        if self.is_irreversible(self.moves.len()) {
+            self.last_irreversible = self.moves.len();
+        }
+        let gen = self.legal_moves();
+        if gen.len() == 0 {
+            self.result = self.board().result_from_moves(&gen);
         }
         self
     }
 
     /// Remove the last board and the last move from the list.
-    /// The board of the game will then be the previous one.
+    /// The board of the game will then be the previous one,
+    /// and the result is reset.
     pub fn undo_last_move(&mut self) -> &Self {
-        self.boards.pop();
-        self.moves.pop();
+        if self.moves.pop().is_some() {
+            self.boards.pop();
+            self.hashes.pop();
+            self.result = GameResult::NoResult;
+            let ply = self.moves.len();
+            if self.last_irreversible > ply {
+                self.last_irreversible = (1..=ply).rev()
+                    .find(|&i| self.is_irreversible(i))
+                    .unwrap_or(0);
+            }
+        }
         self
     }
 
+    /// Undo the last move, which is kept to be played again by
+    /// `Game::redo`. Returns this move, or `None` at the first board.
+    /// 
+    /// ```
+    /// use chess_std::Game;
+    /// 
+    /// let mut game = Game::from_pgn("1. e4 e5 2. Nf3").unwrap();
+    /// let nf3 = game.undo().unwrap();
+    /// let e5 = game.undo().unwrap();
+    /// assert_eq!(game.moves.len(), 1);
+    /// assert_eq!(game.redo(), Some(e5));
+    /// assert_eq!(game.redo(), Some(nf3));
+    /// assert_eq!(game.redo(), None);
+    /// ```
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv = *self.moves.last()?;
+        self.undo_last_move();
+        self.redo.push(mv);
+        Some(mv)
+    }
+
+    /// Play again the last undone move, and return it. Playing another
+    /// move than with `Game::redo` forgets the undone moves.
+    pub fn redo(&mut self) -> Option<Move> {
+        let mv = self.redo.pop()?;
+        self.push_move(mv);
+        Some(mv)
+    }
+
+    // Whether the move leading to the board at this ply cannot be undone:
+    // a capture, a pawn move, or a loss of castling rights.
+    fn is_irreversible(&self, ply: usize) -> bool {
+        let (before, after) = (&self.boards[ply - 1], &self.boards[ply]);
+        after.half_move_clock == 0 || before.rights != after.rights
+    }
+
+    /// The ply of the board after the last capture, pawn move or change
+    /// of castling rights, or 0 when there is none.
+    /// No board before it can be repeated afterwards.
+    ///
+    /// ```
+    /// use chess_std::Game;
+    ///
+    /// let game = Game::from_pgn("1. e4 e5 2. Nf3 Nc6 3. Ke2").unwrap();
+    /// assert_eq!(game.last_irreversible_ply(), 5);
+    /// let game = Game::from_pgn("1. Nf3 Nc6 2. e4").unwrap();
+    /// assert_eq!(game.last_irreversible_ply(), 3);
+    /// ```
+    pub fn last_irreversible_ply(&self) -> usize {
+        self.last_irreversible
+    }
+
+    /// How many times the current position has occurred since the last
+    /// irreversible move, including now.
+    pub fn repetition_count(&self) -> usize {
+        let h = *self.hashes.last().unwrap();
+        self.hashes[self.last_irreversible..].iter().filter(|&x| *x == h).count()
+    }
+
+    /// Whether the current position has already occurred,
+    /// which a search may consider as a draw.
+    pub fn is_repetition(&self) -> bool {
+        self.repetition_count() >= 2
+    }
+
+    /// A hash of the start board and of the moves played, in order.
+    /// Unlike the positional hash, transpositions get different hashes,
+    /// which allows to find duplicate games. The hash of a game is the
+    /// same across runs.
+    pub fn sequence_hash(&self) -> u64 {
+        // FNV-1a over the bytes of each move.
+        const PRIME: u64 = 0x0100_0000_01b3;
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325 ^ self.boards[0].zobrist_hash();
+        for mv in &self.moves {
+            let flag = match mv.flag {
+                MoveFlag::Quiet => 0,
+                MoveFlag::EnPassant(_) => 1,
+                MoveFlag::Castling(side) => 2 + side as u8,
+                MoveFlag::Promotion(ptype) => 4 + ptype.index() as u8,
+            };
+            for byte in [mv.from.index() as u8, mv.to.index() as u8, flag] {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(PRIME);
+            }
+        }
+        hash
+    }
+
+    /// A player resigns: the opponent wins the game.
+    /// 
+    /// ```should_panic
+    /// use chess_std::prelude::*;
+    /// use chess_std::{Game, GameResult, WinType};
+    /// 
+    /// let mut game = Game::from_pgn("1. e4 e5").unwrap();
+    /// game.resign(White);
+    /// assert!(game.is_finished());
+    /// assert_eq!(game.result, GameResult::Win(Black, WinType::Resign));
+    /// assert!(game.to_pgn().ends_with(" 0-1"));
+    /// 
+    /// // No more moves can be played.
+    /// game.play_move(Move::quiet(Square::G1, Square::F3));
+    /// ```
+    pub fn resign(&mut self, who: Color) {
+        assert!(!self.is_finished(), "Resigning when game is finished");
+        self.result = GameResult::Win(who.opponent(), WinType::Resign);
+    }
+
+    /// Both players agree to a draw.
+    /// 
+    /// ```
+    /// use chess_std::{Game, GameResult, DrawType};
+    /// 
+    /// let mut game = Game::from_pgn("1. e4 e5").unwrap();
+    /// game.agree_draw();
+    /// assert!(game.is_finished());
+    /// assert_eq!(game.result, GameResult::Draw(DrawType::Agreement));
+    /// assert!(game.to_pgn().ends_with(" 1/2-1/2"));
+    /// ```
+    pub fn agree_draw(&mut self) {
+        assert!(!self.is_finished(), "Agreeing to a draw when game is finished");
+        self.result = GameResult::Draw(DrawType::Agreement);
+    }
+
     /// See: `Board::in_checkmate`.
     pub fn in_checkmate(&self) -> bool {
         self.board().in_checkmate()
@@ -183,8 +402,7 @@ impl Game {
     /// This completes `Board::can_claim_draw_with` for threefold repetition.
     pub fn can_claim_draw_with(&self, dt: DrawType) -> bool {
         if let DrawType::ThreefoldRepetition = dt {
-            let h = *self.hashes.last().unwrap();
-            self.hashes.iter().filter(|&x| *x == h).count() >= 3
+            self.repetition_count() >= 3
         } else {
             self.board().can_claim_draw_with(dt)
         }
@@ -215,7 +433,9 @@ impl Game {
 
 
 impl Game {
-    /// Parse PGN game data. tags will be ignored.
+    /// Parse PGN game data, until the result if any. The game starts from
+    /// the `FEN` tag when there is one, and the other tags, the comments
+    /// and the variations are ignored.
     /// ```
     /// use chess_std::Game;
     /// 
@@ -229,64 +449,82 @@ impl Game {
     /// ```
     #[cfg(feature = "pgn")]
     pub fn from_pgn(pgn: &str) -> Result<Game, String> {
-        lazy_static! {
-            static ref RE_PGN: Regex = Regex::new(r"(?x)
-            (?P<hmc>\d{1,3})\.         # halfmove clock
-            \s
-            (?P<wmv>\S+)               # White move
-            \s
-            (?P<bmv>\S*)               # Black move
-            \s*
-            ").unwrap();
-        }
-        let mut s = Game::purge_pgn(pgn);
-        if !s.ends_with(' ') {
-            s.push(' '); // Necessary to capture `half-move`
+        let mut tokens = PGNTokenizer::new(pgn).peekable();
+        let mut tags = PGNTags::new();
+        while let Some(&PGNToken::TagPair(tag, value)) = tokens.peek() {
+            tags.add_tag(tag, value.to_owned());
+            tokens.next();
         }
-        let mut game = Game::new();
-        let mut mv = Move::NONE;
-        for caps in RE_PGN.captures_iter(&s[..]) {
-            let halfmove_clock: u32 = caps["hmc"].parse().unwrap();
-            if halfmove_clock - 1 != game.board().half_move_clock {
-                return Err(format!("Invalid halfmove clock: {}", halfmove_clock));
-            }
-            let mut play_move = |k: &str| -> Result<(), String> {
-                mv = game.parse_move(&caps[k]).unwrap_or(Move::NONE);
-                if mv.is_none() {
-                    return Err(format!("Couldn't parse move: {}", &caps[k]));
+        let mut game = match tags.pairs.get("FEN") {
+            Some(fen) if tags.pairs.get("SetUp").is_none_or(|v| v == "1") => {
+                Game::from_board(Board::from_fen(fen)?)
+            },
+            _ => Game::new(),
+        };
+        let mut token_result = None;
+        // The depth of the skipped variations.
+        let mut depth = 0;
+        for token in tokens {
+            match token {
+                PGNToken::Variation(Variation::Open) => depth += 1,
+                PGNToken::Variation(Variation::Close) => {
+                    if depth == 0 {
+                        return Err("Unexpected end of variation".to_owned());
+                    }
+                    depth -= 1;
                 }
-                if !game.is_move_legal(mv) {
-                    return Err(format!("Illegal move: {}", &caps[k]));
+                _ if depth > 0 => {},
+                PGNToken::MoveNumber(n, col) => {
+                    if n != game.board().fullmove_number() || col != game.board().turn {
+                        return Err(format!("Invalid move number: {}", n));
+                    }
                 }
-                game.play_move(mv);
-                Ok(())
-            };
-            play_move("wmv")?;
-            if !caps["bmv"].is_empty() {
-                play_move("bmv")?;
+                PGNToken::San(san) => {
+                    let mv = game.parse_move(san).unwrap_or(Move::NONE);
+                    if mv.is_none() {
+                        return Err(format!("Couldn't parse move: {}", san));
+                    }
+                    if !game.is_move_legal(mv) {
+                        return Err(format!("Illegal move: {}", san));
+                    }
+                    game.play_move(mv);
+                }
+                PGNToken::Result(token) => {
+                    token_result = Game::parse_result_token(token);
+                    break;
+                }
+                PGNToken::TagPair(..) => {
+                    return Err("Unexpected tag pair in the movetext".to_owned());
+                }
+                PGNToken::Comment(_) | PGNToken::Nag(_) => {},
             }
         }
+        if depth > 0 {
+            return Err("Unterminated variation".to_owned());
+        }
+        match token_result {
+            None | Some(GameResult::NoResult) => {},
+            Some(result) if game.is_finished() => {
+                if result.to_string() != game.result.to_string() {
+                    return Err(format!("Result {} does not match the board: {}",
+                                       result, game.result));
+                }
+            }
+            Some(result) => game.result = result,
+        }
         Ok(game)
     }
 
-    // Remove comments and tags.
+    // The result of a PGN result token, when the board does not end the game.
     #[cfg(feature = "pgn")]
-    fn purge_pgn(pgn: &str) -> String {
-        lazy_static! {
-            static ref RE_PURGE: Regex = Regex::new("(?xm)
-            \\[
-                (?P<tag>\\[a-zA-Z]+) # tag name
-                \\s+
-                \"(?P<value>.*?)\"   # quoted tag value
-            \\]
-            |
-            ;.*?$                    # comment
-            |
-            \\{.*?\\}                # comment
-            ").unwrap();
+    fn parse_result_token(token: &str) -> Option<GameResult> {
+        match token {
+            "1-0"     => Some(GameResult::Win(White, WinType::Resign)),
+            "0-1"     => Some(GameResult::Win(Black, WinType::Resign)),
+            "1/2-1/2" => Some(GameResult::Draw(DrawType::Agreement)),
+            "*"       => Some(GameResult::NoResult),
+            _         => None
         }
-        
-        RE_PURGE.replace(pgn, "").to_string()
     }
 
     /// Parse a PGN move, playable at this board.
@@ -319,11 +557,12 @@ impl Game {
         let caps = RE_PIECE.captures_iter(pgn).next().unwrap();
         let ptype = self.parse_piece(&caps)?;
         let (from, to) = self.parse_coordinates(&caps, ptype)?;
+        let flag = self.parse_flags(&caps, ptype, to)?;
 
-        if caps["cap"].len() == 1 && self.board().is_empty(to) {
+        let en_passant = matches!(flag, MoveFlag::EnPassant(_));
+        if caps["cap"].len() == 1 && self.board().is_empty(to) && !en_passant {
             return Err("Erroneous capture indication".to_owned());
         }
-        let flag = self.parse_flags(&caps, to)?;
         Ok(Move{ from, to, flag })
     }
 
@@ -345,14 +584,16 @@ impl Game {
     fn parse_coordinates(&self, caps: &regex::Captures<'_>, ptype: PieceType) ->
             Result<(Square, Square), String> {
         let to = Square::from_san(&caps["dest"])?;
-        let same_piece_here: Vec<Square> = self.board()
+        let mut same_piece_here: Vec<Square> = self.board()
             .legal_moves_of(ptype)
             .filter(|mv| mv.to == to)
             .map(|mv| mv.from)
             .collect();
+        // The promotions of a pawn share their origin.
+        same_piece_here.dedup();
         // Resolve ambiguities
         let from = match same_piece_here.len() {
-            0 => return Err(format!("No legal moves found from {}", ptype)),
+            0 => return Err(format!("No legal moves found from {} to {}", ptype, to)),
             1 => same_piece_here[0],
             _ => {
                 
@@ -363,7 +604,8 @@ impl Game {
                     .filter(|sq| sq.file() == f)
                     .collect();
                 match same_file_here.len() {
-                    0 => return Err(format!("No legal moves found from {} on file {}", ptype, f)),
+                    0 => return Err(format!("No legal moves found from {} on file {} to {}",
+                                            ptype, f, to)),
                     1 => same_file_here[0],
                     _ => {
                         let c = caps["r"].chars().next().unwrap_or(' ');
@@ -377,16 +619,19 @@ impl Game {
     }
 
     #[cfg(feature = "pgn")]
-    fn parse_flags(&self, caps: &regex::Captures<'_>, to: Square) ->
+    fn parse_flags(&self, caps: &regex::Captures<'_>, ptype: PieceType, to: Square) ->
                    Result<MoveFlag, String> {
         use crate::units::Direction;
-        let flag = if caps["ep"].len() == 1 {
-            let dir = Direction::of_pawns(self.board().turn);
-            let sq = to.shift(dir);
-            MoveFlag::EnPassant(sq)
+        let board = self.board();
+        let flag = if ptype == Pawn && board.ep_target == Some(to) {
+            // The `e.p.` suffix is optional.
+            let dir = Direction::of_pawns(board.turn);
+            MoveFlag::EnPassant(to.shift(-dir))
         } else if caps["prom"].len() == 2 {
-            let c = caps["prom"].chars().next().unwrap();
+            let c = caps["prom"].chars().nth(1).unwrap();
             MoveFlag::Promotion(PieceType::try_from(c)?)
+        } else if ptype == Pawn && to.rank() == Rank::last(board.turn) {
+            return Err("A pawn reaching the last rank must promote".to_owned());
         } else {
             MoveFlag::Quiet
         };
@@ -394,22 +639,154 @@ impl Game {
     }
 
     /// Convert this game to a PGN string, without more metadata.
-    /// The moves are translated to the long algebraic notation.
+    /// The moves are translated to the standard algebraic notation,
+    /// and numbered from the first board as in `format_movetext`.
+    ///
+    /// ```
+    /// use chess_std::{Board, Game};
+    ///
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 12").unwrap();
+    /// let mut game = Game::from_board(board);
+    /// game.play_move(game.parse_move("Kd7").unwrap());
+    /// game.play_move(game.parse_move("Ra7+").unwrap());
+    /// assert_eq!(game.to_pgn(), "12... Kd7 13. Ra7+");
+    /// ```
     #[cfg(feature = "pgn")]
     pub fn to_pgn(&self) -> String {
-        let mut s = String::new();
-        for (i, mv) in self.moves.iter().enumerate() {
-            if i % 2 == 0 {
-                s.push_str(&format!(" {}.", i/2 + 1)[..]);
+        let mut s = format_movetext(&self.boards[0], &self.moves);
+        if self.is_finished() {
+            if !s.is_empty() {
+                s.push(' ');
             }
-            s.push_str(&format!(" {}", self.boards[i].pgn_move(*mv))[..]);
+            s.push_str(&self.result.to_string());
         }
-        if self.is_finished() {
+        s
+    }
+
+    /// The movetext of the line up to a number of half-moves. The line goes
+    /// on past the current board with the moves undone by `Game::undo`,
+    /// so stepping back still allows exporting the whole game.
+    /// The result ends the movetext only when the whole finished line is kept.
+    /// The numbering follows the first board, and there is no leading space.
+    ///
+    /// ```
+    /// use chess_std::Game;
+    ///
+    /// let mut game = Game::from_pgn("1. f3 e5 2. g4 Qh4#").unwrap();
+    /// assert_eq!(game.to_pgn_upto(3), "1. f3 e5 2. g4");
+    /// assert_eq!(game.to_pgn_upto(10), "1. f3 e5 2. g4 Qh4# 0-1");
+    /// game.undo();
+    /// game.undo();
+    /// assert_eq!(game.to_pgn_upto(game.moves.len()), "1. f3 e5");
+    /// assert_eq!(game.to_pgn_upto(4), "1. f3 e5 2. g4 Qh4#");
+    /// ```
+    #[cfg(feature = "pgn")]
+    pub fn to_pgn_upto(&self, ply: usize) -> String {
+        let line: Moves = self.moves.iter()
+            .chain(self.redo.iter().rev())
+            .take(ply)
+            .copied()
+            .collect();
+        let mut s = format_movetext(&self.boards[0], &line);
+        if ply >= self.moves.len() && self.redo.is_empty() && self.is_finished() {
             s.push_str(&format!(" {}", self.result));
         }
         s
     }
 
+    /// Convert this game to a complete PGN: the seven tag roster first,
+    /// then the other tags sorted by name, and the movetext ended by the
+    /// result. Missing roster tags are filled with unknown values,
+    /// and the `Result` tag always follows the result of the game.
+    /// A game that doesn't start from the initial position gets the
+    /// `SetUp` and `FEN` tags after the roster, in place of given ones.
+    /// 
+    /// ```
+    /// use chess_std::{Game, PGNTags};
+    /// 
+    /// let mut tags = PGNTags::new();
+    /// tags.add_tag("White", "Anderssen".to_owned());
+    /// tags.add_tag("Result", "1-0".to_owned());
+    /// let pgn = Game::from_pgn("1. e4 e5").unwrap().to_pgn_with_tags(&tags);
+    /// assert!(pgn.starts_with("[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n"));
+    /// assert!(pgn.contains("[White \"Anderssen\"]\n[Black \"?\"]\n[Result \"*\"]\n"));
+    /// assert!(pgn.ends_with("\n\n1. e4 e5 *\n"));
+    /// ```
+    #[cfg(feature = "pgn")]
+    pub fn to_pgn_with_tags(&self, tags: &PGNTags) -> String {
+        const ROSTER: [(&str, &str); 6] = [
+            ("Event", "?"), ("Site", "?"), ("Date", "????.??.??"),
+            ("Round", "?"), ("White", "?"), ("Black", "?"),
+        ];
+        let mut s = String::new();
+        for (tag, unknown) in &ROSTER {
+            let value = tags.pairs.get(*tag).map_or(*unknown, String::as_str);
+            s.push_str(&format!("[{} \"{}\"]\n", tag, value));
+        }
+        s.push_str(&format!("[Result \"{}\"]\n", self.result));
+        let setup = !self.boards[0].exact_eq(&Board::new());
+        if setup {
+            s.push_str(&format!("[SetUp \"1\"]\n[FEN \"{}\"]\n", self.boards[0].to_fen()));
+        }
+        let mut others: Vec<_> = tags.pairs.iter()
+            .filter(|(tag, _)| tag.as_str() != "Result" && !ROSTER.iter().any(|(r, _)| r == tag))
+            .filter(|(tag, _)| !setup || !matches!(tag.as_str(), "SetUp" | "FEN"))
+            .collect();
+        others.sort();
+        for (tag, value) in others {
+            s.push_str(&format!("[{} \"{}\"]\n", tag, value));
+        }
+        let mut movetext = format_movetext(&self.boards[0], &self.moves);
+        if !movetext.is_empty() {
+            movetext.push(' ');
+        }
+        movetext.push_str(&self.result.to_string());
+        s.push_str(&format!("\n{}\n", movetext));
+        s
+    }
+
+    /// The SAN of every move played, in order, each one written from
+    /// the board it was played on. Unlike `Game::to_pgn`, there are
+    /// no move numbers.
+    #[cfg(feature = "pgn")]
+    pub fn moves_san(&self) -> Vec<String> {
+        self.boards.iter()
+            .zip(self.moves.iter())
+            .map(|(board, mv)| board.to_san(*mv))
+            .collect()
+    }
+
+}
+
+/// Format moves played from a board as numbered SAN movetext, e.g. `1. e4 e5 2. Nf3`.
+/// When Black plays first, the first number is followed by an ellipsis: `1... e5 2. Nf3`.
+/// 
+/// The numbering starts at the fullmove number of the board.
+/// The moves are assumed to be legal.
+/// 
+/// ```
+/// use chess_std::prelude::*;
+/// use chess_std::{Board, format_movetext};
+/// 
+/// let moves = [Move::quiet(Square::E2, Square::E4), Move::quiet(Square::E7, Square::E5)];
+/// assert_eq!(format_movetext(&Board::new(), &moves), "1. e4 e5");
+/// let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 3").unwrap();
+/// assert_eq!(format_movetext(&board, &moves[1..]), "3... e5");
+/// ```
+#[cfg(feature = "pgn")]
+pub fn format_movetext(start: &Board, moves: &[Move]) -> String {
+    let mut s = String::new();
+    let mut board = start.clone();
+    for (i, &mv) in moves.iter().enumerate() {
+        if board.turn == White {
+            s.push_str(&format!(" {}.", board.fullmove_number()));
+        } else if i == 0 {
+            s.push_str(&format!(" {}...", board.fullmove_number()));
+        }
+        s.push_str(&format!(" {}", board.to_san(mv)));
+        board.apply_move(mv);
+    }
+    s.trim_start().to_owned()
 }
 
 
@@ -441,18 +818,12 @@ impl PGNTags {
 
     /// Extract tags from PGN.
     pub fn from_pgn(pgn: &str) -> Self {
-        lazy_static! {
-            static ref RE_TAGS: Regex = Regex::new("(?x)
-            \\[
-                (?P<tag>\\[a-zA-Z]+) # tag name
-                \\s+                
-                \"(?P<value>.*?)\"   # tag value in quotes
-            \\]
-            ").unwrap();
-        }
         let mut meta = Self::new();
-        for cap in RE_TAGS.captures_iter(pgn) {
-            meta.pairs.insert(cap["tag"].to_string(), cap["value"].to_string());
+        for token in PGNTokenizer::new(pgn) {
+            match token {
+                PGNToken::TagPair(tag, value) => meta.add_tag(tag, value.to_owned()),
+                _ => break,
+            }
         }
         meta
     }
@@ -516,6 +887,15 @@ pub enum GameResult {
     Draw(DrawType),
 }
 
+/// The state of a board for the player to move, see `Board::status`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate(Color), // The winner
+    Stalemate,
+    DrawClaimable(DrawType),
+}
+
 use std::fmt;
 
 impl fmt::Display for GameResult {
@@ -538,13 +918,41 @@ impl fmt::Display for GameResult {
 
 /// A TreeNode stores its game board and knows its position on the tree.
 #[cfg(feature = "trees")]
-#[derive(Clone, PartialEq)]
 pub struct TreeNode {
     board: BoardRef,
+    mv: Option<Move>,                   // The move that leads to this node
+    comment: Option<String>,
+    this: Weak<RefCell<TreeNode>>,      // The reference that owns this node
     parent: Option<TreeNodeRef>,
     children: NodeChildren
 }
 
+/// A clone is detached from the reference of the original node.
+#[cfg(feature = "trees")]
+impl Clone for TreeNode {
+    fn clone(&self) -> Self {
+        TreeNode{
+            board: self.board.clone(),
+            mv: self.mv,
+            comment: self.comment.clone(),
+            this: Weak::new(),
+            parent: self.parent.clone(),
+            children: self.children.clone()
+        }
+    }
+}
+
+/// Nodes are equal when their boards, moves, comments and subtrees are.
+#[cfg(feature = "trees")]
+impl PartialEq for TreeNode {
+    fn eq(&self, other: &TreeNode) -> bool {
+        self.board == other.board &&
+        self.mv == other.mv &&
+        self.comment == other.comment &&
+        self.children == other.children
+    }
+}
+
 #[cfg(feature = "trees")]
 type TreeNodeRef = Rc<RefCell<TreeNode>>;
 #[cfg(feature = "trees")]
@@ -558,6 +966,9 @@ impl TreeNode {
     pub fn new_root(board: Board) -> TreeNode {
         TreeNode{
             board: RefCell::new(board),
+            mv: None,
+            comment: None,
+            this: Weak::new(),
             parent: None,
             children: Vec::new()
         }
@@ -566,13 +977,70 @@ impl TreeNode {
     /// A new node that leads to multiple branches.
     pub fn new_root_with_children(
             board: Board, children: NodeChildren) -> TreeNode {
+        for child in &children {
+            child.borrow_mut().this = Rc::downgrade(child);
+        }
         TreeNode{
             board: RefCell::new(board),
+            mv: None,
+            comment: None,
+            this: Weak::new(),
             parent: None,
             children
         }
     }
 
+    // A leaf reached by playing a move from its parent.
+    fn new_leaf(board: Board, mv: Move, parent: TreeNodeRef) -> TreeNode {
+        TreeNode{
+            board: RefCell::new(board),
+            mv: Some(mv),
+            comment: None,
+            this: Weak::new(),
+            parent: Some(parent),
+            children: Vec::new()
+        }
+    }
+
+    /// Wrap this node into a shared reference, that the node keeps track of.
+    pub fn into_ref(self) -> TreeNodeRef {
+        Rc::new_cyclic(|this| {
+            let mut node = self;
+            node.this = this.clone();
+            RefCell::new(node)
+        })
+    }
+
+    /// The board of this node.
+    pub fn board(&self) -> Ref<'_, Board> {
+        self.board.borrow()
+    }
+
+    /// The move that leads to this node, `None` for the root.
+    pub fn last_move(&self) -> Option<Move> {
+        self.mv
+    }
+
+    /// The comment attached to the move of this node, if any.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Attach a comment to this node, replacing the previous one.
+    pub fn set_comment(&mut self, comment: String) {
+        self.comment = Some(comment);
+    }
+
+    /// The parent of this node, `None` for the root.
+    pub fn parent(&self) -> Option<TreeNodeRef> {
+        self.parent.clone()
+    }
+
+    /// The children of this node. The first one is the main line.
+    pub fn children(&self) -> &[TreeNodeRef] {
+        &self.children
+    }
+
     /// Whether this node has no parent.
     pub fn is_root(&self) -> bool {
         self.parent.is_none()
@@ -593,21 +1061,51 @@ impl TreeNode {
         self.children.len()
     }
 
-    /// Add a node to the children vector. This does not mutate the new child.
+    /// Add a node to the children vector. Apart from its self-reference,
+    /// this does not mutate the new child.
     pub fn add_child(&mut self, child: TreeNodeRef) {
+        child.borrow_mut().this = Rc::downgrade(&child);
         self.children.push(child);
     }
 
-    /// Insert a node in the children vector, without mutating it.
+    /// Insert a node in the children vector. Apart from its self-reference,
+    /// this does not mutate the new child.
     pub fn insert_child(&mut self, child: TreeNodeRef, index: usize) {
+        child.borrow_mut().this = Rc::downgrade(&child);
         self.children.insert(index, child);
     }
 
-    /// Returns the index of a node in the children vector.
+    /// Play a legal move from this node's board, and add the resulting node
+    /// as the last child.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::{Board, Tree};
+    /// 
+    /// let tree = Tree::from_board(Board::new());
+    /// let mv = Move::quiet(Square::G1, Square::F3);
+    /// let child = tree.root.borrow_mut().add_move(mv).unwrap();
+    /// assert_eq!(*child.borrow().board(), Board::new().play_move(mv));
+    /// 
+    /// let illegal = Move::quiet(Square::G1, Square::G3);
+    /// assert!(tree.root.borrow_mut().add_move(illegal).is_err());
+    /// ```
+    pub fn add_move(&mut self, mv: Move) -> Result<TreeNodeRef, String> {
+        if !self.board().is_move_legal(mv) {
+            return Err(format!("Illegal move: {}", mv));
+        }
+        let me = self.this.upgrade()
+            .ok_or_else(|| "Node is not referenced by the tree".to_owned())?;
+        let board = self.board().play_move(mv);
+        let child = TreeNode::new_leaf(board, mv, me).into_ref();
+        self.add_child(child.clone());
+        Ok(child)
+    }
+
+    /// Returns the index of a node in the children vector,
+    /// comparing the references rather than the nodes.
     pub fn index_child(&self, child: TreeNodeRef) -> Option<usize> {
-        // Rc Equality will be propagated to RefCell, then to TreeNode
-        // FIXME: verify if true ???
-        self.children.iter().position(|x| x.eq(&child))
+        self.children.iter().position(|x| Rc::ptr_eq(x, &child))
     }
 
     /// Remove a node at an index, but does not remove its parent.
@@ -624,14 +1122,11 @@ impl TreeNode {
 
     /// Remove this node from parent and set this node's parent to None.
     pub fn cut(&mut self) {
-        if let Some(parent) = self.parent.clone() {
-            // FIXME: don't clone self...?
-            
-            let me = Rc::from(RefCell::new(self.clone()));
-            let my_pos = parent.borrow().index_child(me).unwrap();
-            parent.borrow_mut().remove(my_pos);
+        if let Some(parent) = self.parent.take() {
+            if let Some(me) = self.this.upgrade() {
+                parent.borrow_mut().remove_child(me);
+            }
         }
-        self.parent = None;
     }
 
     // Cut from parent and assign a new parent to this node.
@@ -660,19 +1155,92 @@ impl Default for Tree {
 impl Tree {
     pub fn new() -> Tree {
         let root = TreeNode::new_root(Board::default());
-        Tree{root: Rc::new(RefCell::new(root))}
+        Tree{root: root.into_ref()}
     }
 
-    /// Iterate over the "left-most" sequence.
-    pub fn iter(&self) -> TreeIterator {
+    /// A tree whose root is a specific board.
+    pub fn from_board(board: Board) -> Tree {
+        let root = TreeNode::new_root(board);
+        Tree{root: root.into_ref()}
+    }
+
+    /// Iterate over the "left-most" sequence, after the root.
+    pub fn mainline(&self) -> TreeIterator {
         TreeIterator{current: self.root.clone()}
-    }    
+    }
+
+    /// Iterate over every node of the tree with its depth, in depth-first order.
+    /// The root comes first with a depth of 0, and the main line of a node
+    /// is visited before its variations.
+    pub fn iter_dfs(&self) -> TreeDfsIterator {
+        TreeDfsIterator{stack: vec![(self.root.clone(), 0)]}
+    }
+}
+
+#[cfg(all(feature = "trees", feature = "pgn"))]
+impl Tree {
+    /// Parse PGN game data, including the recursive annotation variations.
+    /// Tags will be ignored.
+    /// 
+    /// The main line is always the first child of a node, and a comment
+    /// is stored on the node of the move that precedes it.
+    /// 
+    /// ```
+    /// use chess_std::Tree;
+    /// 
+    /// let pgn = "1. e4 e5 (1... c5 {Sicilian}) 2. Nf3 *";
+    /// let tree = Tree::from_pgn(pgn).unwrap();
+    /// let e4 = tree.root.borrow().children()[0].clone();
+    /// assert!(e4.borrow().is_branch());
+    /// let c5 = e4.borrow().children()[1].clone();
+    /// assert_eq!(c5.borrow().comment(), Some("Sicilian"));
+    /// ```
+    pub fn from_pgn(pgn: &str) -> Result<Tree, String> {
+        let tree = Tree::from_board(Board::new());
+        let mut current = tree.root.clone();
+        // The nodes to come back to when a variation is closed.
+        let mut variations: Vec<TreeNodeRef> = Vec::new();
+        for token in PGNTokenizer::new(pgn) {
+            match token {
+                PGNToken::Comment(comment) => {
+                    current.borrow_mut().set_comment(comment.to_owned());
+                }
+                PGNToken::Variation(Variation::Open) => {
+                    // The variation replaces the last move.
+                    let parent = current.borrow().parent()
+                        .ok_or_else(|| "Variation without a previous move".to_owned())?;
+                    variations.push(current);
+                    current = parent;
+                }
+                PGNToken::Variation(Variation::Close) => {
+                    current = variations.pop()
+                        .ok_or_else(|| "Unexpected end of variation".to_owned())?;
+                }
+                PGNToken::San(san) => {
+                    current = Tree::play_san(&current, san)?;
+                }
+                _ => {}
+            }
+        }
+        if !variations.is_empty() {
+            return Err("Unterminated variation".to_owned());
+        }
+        Ok(tree)
+    }
+
+    // Play a SAN move from a node, and return the new child.
+    fn play_san(node: &TreeNodeRef, san: &str) -> Result<TreeNodeRef, String> {
+        let board = node.borrow().board().clone();
+        let mv = Game::from_board(board).parse_move(san)?;
+        node.borrow_mut().add_move(mv)
+            .map_err(|_| format!("Illegal move: {}", san))
+    }
 }
 
 
 
 
-#[doc(hidden)]
+/// An iterator over the main line of a `Tree`.
 #[cfg(feature = "trees")]
 pub struct TreeIterator {
     current: TreeNodeRef
@@ -691,4 +1259,379 @@ impl Iterator for TreeIterator {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+/// A depth-first iterator over all the nodes of a `Tree`, with their depth.
+#[cfg(feature = "trees")]
+pub struct TreeDfsIterator {
+    stack: Vec<(TreeNodeRef, usize)>
+}
+
+#[cfg(feature = "trees")]
+impl Iterator for TreeDfsIterator {
+    type Item = (TreeNodeRef, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, depth) = self.stack.pop()?;
+        // Push in reverse order, so that the main line is visited first.
+        for child in node.borrow().children().iter().rev() {
+            self.stack.push((child.clone(), depth + 1));
+        }
+        Some((node, depth))
+    }
+}
+
+
+#[cfg(all(test, feature = "pgn"))]
+mod pgn_test {
+    use super::*;
+
+    #[test]
+    fn test_parse_promotions() -> Result<(), String> {
+        let game = Game::from_board(Board::from_fen("3r3k/2P1P3/8/8/8/8/8/4K3 w - - 0 1")?);
+        assert_eq!(game.parse_move("exd8=Q")?, Move::promotion(Square::E7, Square::D8, Queen));
+        assert_eq!(game.parse_move("cxd8=R+")?, Move::promotion(Square::C7, Square::D8, Rook));
+        assert_eq!(game.parse_move("e8=Q+")?, Move::promotion(Square::E7, Square::E8, Queen));
+        assert_eq!(game.parse_move("c8=N")?, Move::promotion(Square::C7, Square::C8, Knight));
+        assert!(game.parse_move("exd8=K").is_err());
+
+        let game = Game::from_board(Board::from_fen("4k3/8/8/8/8/8/2p5/1N2K3 b - - 0 1")?);
+        assert_eq!(game.parse_move("cxb1=N")?, Move::promotion(Square::C2, Square::B1, Knight));
+        assert_eq!(game.parse_move("c1=Q+")?, Move::promotion(Square::C2, Square::C1, Queen));
+
+        let game = Game::from_pgn("1. e4 Nf6 2. e5 d5")?;
+        let ep = Move::en_passant(Square::E5, Square::D6, Square::D5);
+        assert_eq!(game.parse_move("exd6")?, ep);
+        assert_eq!(game.parse_move("exd6 e.p.")?, ep);
+        assert!(game.parse_move("exf6")?.flag == Quiet);
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let game = Game::from_pgn("1. f3 e5 2. Nc3 d5 3. g4 Qh4#").unwrap();
+        let mut undone = game.clone();
+        while undone.undo().is_some() {}
+        assert_eq!(undone.board(), &Board::new());
+        assert_eq!((undone.boards.len(), undone.hashes.len()), (1, 1));
+        assert_eq!(undone.result, GameResult::NoResult);
+        while undone.redo().is_some() {}
+        assert_eq!(undone.moves, game.moves);
+        assert_eq!(undone.boards, game.boards);
+        assert_eq!(undone.hashes, game.hashes);
+        assert_eq!(undone.last_irreversible_ply(), game.last_irreversible_ply());
+        assert_eq!(undone.result, game.result);
+
+        // Another move forgets the undone moves.
+        undone.undo();
+        undone.undo();
+        undone.play_move(Move::quiet(Square::G1, Square::H3));
+        assert_eq!(undone.redo(), None);
+        assert_eq!(undone.hashes.len(), undone.boards.len());
+    }
+
+    #[test]
+    fn test_sequence_hash() {
+        let game = Game::from_pgn("1. e4 e5 2. Nf3 Nc6").unwrap();
+        let transposed = Game::from_pgn("1. Nf3 e5 2. e4 Nc6").unwrap();
+        assert_eq!(game.board().zobrist_hash(), transposed.board().zobrist_hash());
+        assert_ne!(game.sequence_hash(), transposed.sequence_hash());
+        assert_eq!(game.sequence_hash(), game.clone().sequence_hash());
+        assert_eq!(game.sequence_hash(), Game::from_pgn("1. e4 e5 2. Nf3 Nc6").unwrap().sequence_hash());
+
+        let mut shorter = game.clone();
+        shorter.undo_last_move();
+        assert_ne!(shorter.sequence_hash(), game.sequence_hash());
+        assert_ne!(Game::new().sequence_hash(), shorter.sequence_hash());
+    }
+
+    #[test]
+    fn test_moves_san() {
+        let game = Game::from_pgn("1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7#").unwrap();
+        assert_eq!(game.moves_san(), vec!["e4", "e5", "Qh5", "Nc6", "Bc4", "Nf6", "Qxf7#"]);
+        assert!(Game::new().moves_san().is_empty());
+    }
+
+    #[test]
+    fn test_threefold_window() {
+        let shuffle = "1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3 Nf6 4. Ng1 Ng8";
+        let mut game = Game::from_pgn(shuffle).unwrap();
+        assert_eq!(game.last_irreversible_ply(), 0);
+        assert!(game.can_claim_draw_with(DrawType::ThreefoldRepetition));
+        game.undo_last_move();
+        assert!(!game.can_claim_draw_with(DrawType::ThreefoldRepetition));
+
+        // The pawn moves start a new window.
+        let mut game = Game::from_pgn(
+            "1. e3 e6 2. Nf3 Nf6 3. Ng1 Ng8 4. Nf3 Nf6 5. Ng1 Ng8"
+        ).unwrap();
+        assert_eq!(game.last_irreversible_ply(), 2);
+        assert!(game.can_claim_draw_with(DrawType::ThreefoldRepetition));
+        for _ in 0..8 {
+            game.undo_last_move();
+        }
+        assert_eq!(game.last_irreversible_ply(), 2);
+        game.undo_last_move();
+        assert_eq!(game.last_irreversible_ply(), 1);
+        game.undo_last_move();
+        assert_eq!(game.last_irreversible_ply(), 0);
+    }
+
+    #[test]
+    fn test_repetition_count() {
+        let mut game = Game::from_pgn("1. Nf3 Nf6 2. Ng1 Ng8").unwrap();
+        assert_eq!(game.repetition_count(), 2);
+        assert!(game.is_repetition());
+        game.undo_last_move();
+        assert_eq!(game.repetition_count(), 1);
+        assert!(!game.is_repetition());
+        assert_eq!(Game::new().repetition_count(), 1);
+
+        // The start position is behind the pawn moves.
+        let game = Game::from_pgn("1. e3 e6 2. Nf3 Nf6 3. Ng1 Ng8").unwrap();
+        assert_eq!(game.repetition_count(), 2);
+        let game = Game::from_pgn("1. e3 e6 2. Ke2 Ke7 3. Ke1 Ke8").unwrap();
+        assert_eq!(game.repetition_count(), 1);
+    }
+
+    #[test]
+    fn test_from_pgn_result() {
+        let game = Game::from_pgn("1. e4 e5 2. Nf3 1-0").unwrap();
+        assert_eq!(game.result, GameResult::Win(White, WinType::Resign));
+        assert_eq!(game.moves.len(), 3);
+        let game = Game::from_pgn("1. e4 e5 2. Nf3 Nc6 0-1").unwrap();
+        assert_eq!(game.result, GameResult::Win(Black, WinType::Resign));
+        let game = Game::from_pgn("1. e4 e5 1/2-1/2").unwrap();
+        assert_eq!(game.result, GameResult::Draw(DrawType::Agreement));
+        let game = Game::from_pgn("1. e4 e5 *").unwrap();
+        assert_eq!(game.result, GameResult::NoResult);
+        assert!(!game.is_finished());
+    }
+
+    #[test]
+    fn test_from_pgn_result_mismatch() {
+        let mate = "1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7#";
+        let game = Game::from_pgn(&format!("{} 1-0", mate)).unwrap();
+        assert_eq!(game.result, GameResult::Win(White, WinType::Checkmate));
+        assert!(Game::from_pgn(&format!("{} 0-1", mate)).is_err());
+        assert!(Game::from_pgn(&format!("{} 1/2-1/2", mate)).is_err());
+    }
+
+    #[test]
+    fn test_pgn_move_display() {
+        let san = |fen: &str, mv: Move| {
+            Board::from_fen(fen).unwrap().pgn_move(mv).to_string()
+        };
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/3PP3/8/PPP2PPP/RNBQKBNR w KQkq - 1 3";
+        assert_eq!(san(fen, Move::quiet(Square::D4, Square::E5)), "dxe5");
+        assert_eq!(san(fen, Move::quiet(Square::G1, Square::F3)), "Nf3");
+        assert_eq!(san(fen, Move::quiet(Square::D1, Square::H5)), "Qh5");
+        assert_eq!(san(fen, Move::quiet(Square::D4, Square::D5)), "d5");
+        let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+        assert_eq!(san(fen, Move::en_passant(Square::E5, Square::F6, Square::F5)), "exf6");
+        let fen = "2r1k3/1P6/8/8/8/8/8/4K3 w - - 0 1";
+        assert_eq!(san(fen, Move::promotion(Square::B7, Square::B8, Queen)), "b8=Q");
+        assert_eq!(san(fen, Move::promotion(Square::B7, Square::C8, Queen)), "bxc8=Q+");
+        assert_eq!(san(fen, Move::promotion(Square::B7, Square::C8, Knight)), "bxc8=N");
+        let fen = "4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1";
+        assert_eq!(san(fen, Move::castling(White, Side::King)), "O-O");
+        assert_eq!(san(fen, Move::quiet(Square::A1, Square::A8)), "Ra8+");
+    }
+
+    #[test]
+    fn test_to_pgn_upto() -> Result<(), String> {
+        let game = Game::from_pgn("1. e4 e5 2. Nf3 Nc6 3. Bb5 a6")?;
+        assert_eq!(game.to_pgn_upto(0), "");
+        assert_eq!(game.to_pgn_upto(3), "1. e4 e5 2. Nf3");
+        assert_eq!(game.to_pgn_upto(4), "1. e4 e5 2. Nf3 Nc6");
+        assert_eq!(game.to_pgn_upto(6), game.to_pgn());
+
+        // Black plays first.
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")?;
+        let mut game = Game::from_board(board);
+        for san in ["e5", "Nf3", "Nc6", "Bb5"] {
+            let mv = game.parse_move(san)?;
+            game.play_move(mv);
+        }
+        assert_eq!(game.to_pgn_upto(1), "1... e5");
+        assert_eq!(game.to_pgn_upto(2), "1... e5 2. Nf3");
+        assert_eq!(game.to_pgn_upto(3), "1... e5 2. Nf3 Nc6");
+        game.undo();
+        game.undo();
+        assert_eq!(game.to_pgn_upto(game.moves.len()), "1... e5 2. Nf3");
+        assert_eq!(game.to_pgn_upto(4), "1... e5 2. Nf3 Nc6 3. Bb5");
+
+        // The numbering goes on from the fullmove number of the FEN.
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR b KQkq - 3 3";
+        let pgn = format!("[SetUp \"1\"]\n[FEN \"{}\"]\n\n3... Nd4 4. Qxf7# 1-0", fen);
+        let mut game = Game::from_pgn(&pgn)?;
+        assert_eq!(game.to_pgn_upto(1), "3... Nd4");
+        assert_eq!(game.to_pgn_upto(2), "3... Nd4 4. Qxf7# 1-0");
+        game.undo();
+        let upto = game.to_pgn_upto(game.moves.len());
+        assert_eq!(upto, "3... Nd4");
+        let pgn = format!("[SetUp \"1\"]\n[FEN \"{}\"]\n\n{}", fen, upto);
+        assert_eq!(Game::from_pgn(&pgn)?.moves, game.moves);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_movetext() {
+        let game = Game::from_pgn("1. e4 e5 2. Nf3 Nc6 3. Bb5").unwrap();
+        assert_eq!(format_movetext(&game.boards[0], &game.moves),
+                   "1. e4 e5 2. Nf3 Nc6 3. Bb5");
+        // Black to move first.
+        assert_eq!(format_movetext(&game.boards[1], &game.moves[1..]),
+                   "1... e5 2. Nf3 Nc6 3. Bb5");
+        assert_eq!(format_movetext(&game.boards[1], &game.moves[1..2]), "1... e5");
+        assert_eq!(format_movetext(&Board::new(), &[]), "");
+
+        // Black to move at the third move.
+        let board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3").unwrap();
+        let mut game = Game::from_board(board.clone());
+        for san in ["Nf6", "Ng5", "d5"] {
+            let mv = game.parse_move(san).unwrap();
+            game.play_move(mv);
+        }
+        assert_eq!(format_movetext(&board, &game.moves), "3... Nf6 4. Ng5 d5");
+    }
+
+    #[test]
+    fn test_from_pgn_annotated() {
+        let pgn = "[Event \"Casual\"]\n\n\
+                   1. e4 $1 {Best by test} e5 (1... c5!? ; Sicilian\n\
+                   2. Nf3 (2.c3) d6) 2.Nf3 Nc6?! 1-0 3. Bb5";
+        let game = Game::from_pgn(pgn).unwrap();
+        assert_eq!(game.moves.len(), 4);
+        assert_eq!(game.moves[2], Move::quiet(Square::G1, Square::F3));
+        assert_eq!(game.result, GameResult::Win(White, WinType::Resign));
+        assert!(Game::from_pgn("1. e4 (1. d4 e5").is_err());
+        assert!(Game::from_pgn("1. e4 e5) 2. Nf3").is_err());
+    }
+
+    #[test]
+    fn test_from_pgn_setup() {
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR b KQkq - 3 3";
+        let pgn = format!("[Event \"Puzzle\"]\n[SetUp \"1\"]\n[FEN \"{}\"]\n\n3... Nd4 4. Qxf7# 1-0", fen);
+        let game = Game::from_pgn(&pgn).unwrap();
+        assert!(game.boards[0].exact_eq(&Board::from_fen(fen).unwrap()));
+        assert_eq!(game.moves[..], [Move::quiet(Square::C6, Square::D4),
+                                    Move::quiet(Square::F3, Square::F7)]);
+        assert_eq!(game.result, GameResult::Win(White, WinType::Checkmate));
+        // The numbering follows the fullmove number of the FEN.
+        assert!(Game::from_pgn(&pgn.replace("3... Nd4 4.", "1... Nd4 2.")).is_err());
+        assert!(Game::from_pgn(&pgn.replace("3... Nd4 4. Qxf7#", "3. Qxf7#")).is_err());
+        // Without the tags, the game starts from the initial position.
+        assert!(Game::from_pgn("3... Nd4 4. Qxf7# 1-0").is_err());
+
+        // The exported game keeps its start board and numbering.
+        let exported = game.to_pgn_with_tags(&PGNTags::from_pgn(&pgn));
+        assert!(exported.contains(&format!("[Result \"1-0\"]\n[SetUp \"1\"]\n[FEN \"{}\"]\n", fen)));
+        assert_eq!(exported.matches("[FEN ").count(), 1);
+        assert!(exported.ends_with("\n\n3... Nd4 4. Qxf7# 1-0\n"));
+        let reread = Game::from_pgn(&exported).unwrap();
+        assert!(reread.boards[0].exact_eq(&game.boards[0]));
+        assert_eq!(reread.moves, game.moves);
+        assert_eq!(reread.result, game.result);
+        // No tags for the initial position.
+        assert!(!Game::from_pgn("1. e4").unwrap().to_pgn_with_tags(&PGNTags::new()).contains("[FEN "));
+    }
+}
+
+#[cfg(all(test, feature = "trees", feature = "pgn"))]
+mod tree_test {
+    use super::*;
+
+    #[test]
+    fn test_from_pgn_variation() {
+        let pgn = "[Event \"Test\"]\n\
+                   1. e4 {King pawn} e5 (1... c5 2. Nf3 (2. c3) d6) 2. Nf3 Nc6 *";
+        let tree = Tree::from_pgn(pgn).unwrap();
+        let e4 = tree.root.borrow().children()[0].clone();
+        assert_eq!(e4.borrow().comment(), Some("King pawn"));
+        assert_eq!(e4.borrow().last_move(), Some(Move::quiet(Square::E2, Square::E4)));
+        assert_eq!(e4.borrow().num_children(), 2);
+
+        // The main line comes first.
+        let e5 = e4.borrow().children()[0].clone();
+        assert_eq!(e5.borrow().last_move(), Some(Move::quiet(Square::E7, Square::E5)));
+        assert_eq!(e5.borrow().num_children(), 1);
+        let nf3 = e5.borrow().children()[0].clone();
+        assert_eq!(nf3.borrow().num_children(), 1);
+
+        // The nested variation branches after 1... c5.
+        let c5 = e4.borrow().children()[1].clone();
+        assert_eq!(c5.borrow().last_move(), Some(Move::quiet(Square::C7, Square::C5)));
+        assert!(c5.borrow().is_branch());
+        let c3 = c5.borrow().children()[1].clone();
+        assert!(c3.borrow().is_leaf());
+        assert_eq!(c3.borrow().board().turn, Black);
+    }
+
+    #[test]
+    fn test_cut() {
+        let tree = Tree::from_pgn("1. e4 (1. d4) (1. c4) e5").unwrap();
+        let root = tree.root.clone();
+        assert_eq!(root.borrow().num_children(), 3);
+        let d4 = root.borrow().children()[1].clone();
+        d4.borrow_mut().cut();
+        assert_eq!(root.borrow().num_children(), 2);
+        assert!(d4.borrow().is_root());
+        assert_eq!(root.borrow().index_child(d4), None);
+
+        // The main line is untouched.
+        let e4 = root.borrow().children()[0].clone();
+        assert_eq!(root.borrow().index_child(e4.clone()), Some(0));
+        let c4 = root.borrow().children()[1].clone();
+        c4.borrow_mut().reparent(e4.clone());
+        assert_eq!(root.borrow().num_children(), 1);
+        assert!(Rc::ptr_eq(&c4.borrow().parent().unwrap(), &e4));
+    }
+
+    #[test]
+    fn test_iterators() {
+        let tree = Tree::from_pgn("1. e4 (1. d4 d5 (1... Nf6)) e5 (1... c5) 2. Nf3").unwrap();
+        let nodes: Vec<_> = tree.iter_dfs().collect();
+        assert_eq!(nodes.len(), 8);
+        for (i, (node, _)) in nodes.iter().enumerate() {
+            assert!(nodes[..i].iter().all(|(other, _)| !Rc::ptr_eq(node, other)));
+        }
+        let depths: Vec<_> = nodes.iter().map(|(_, depth)| *depth).collect();
+        assert_eq!(depths, vec![0, 1, 2, 3, 2, 1, 2, 2]);
+        assert!(Rc::ptr_eq(&nodes[0].0, &tree.root));
+
+        let mainline: Vec<_> = tree.mainline()
+            .map(|node| node.borrow().last_move().unwrap().to)
+            .collect();
+        assert_eq!(mainline, vec![Square::E4, Square::E5, Square::F3]);
+    }
+
+    #[test]
+    fn test_add_move() {
+        let tree = Tree::from_board(Board::new());
+        let e4 = Move::quiet(Square::E2, Square::E4);
+        let child = tree.root.borrow_mut().add_move(e4).unwrap();
+        assert_eq!(*child.borrow().board(), tree.root.borrow().board().play_move(e4));
+        assert_eq!(child.borrow().last_move(), Some(e4));
+        assert!(Rc::ptr_eq(&child.borrow().parent().unwrap(), &tree.root));
+
+        let c5 = Move::quiet(Square::C7, Square::C5);
+        let e5 = Move::quiet(Square::E7, Square::E5);
+        child.borrow_mut().add_move(e5).unwrap();
+        child.borrow_mut().add_move(c5).unwrap();
+        assert!(child.borrow().is_branch());
+        assert!(child.borrow_mut().add_move(e4).is_err());
+
+        // A node which is not shared cannot be the parent of a new node.
+        let mut orphan = TreeNode::new_root(Board::new());
+        assert!(orphan.add_move(e4).is_err());
+    }
+
+    #[test]
+    fn test_from_pgn_errors() {
+        assert!(Tree::from_pgn("1. e4 (1. d4").is_err());
+        assert!(Tree::from_pgn("(1. e4)").is_err());
+        assert!(Tree::from_pgn("1. e4 e5)").is_err());
+        assert!(Tree::from_pgn("1. e5").is_err());
+    }
+}
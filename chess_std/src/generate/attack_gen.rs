@@ -33,6 +33,16 @@ pub fn write_in(f: &mut fs::File) -> IoResult<()> {
     write_bb_grids(f, &pawn_pushes)?;
     write!(f, "const PAWN_ATTACKS: [Grid<Bitboard>; NUM_PLAYERS] = ")?;
     write_bb_grids(f, &pawn_attacks)?;
+
+    writeln!(f, "const KING_DISTANCE: Grid<Grid<u8>> = [")?;
+    for from in Square::A1..=Square::H8 {
+        write!(f, "    [")?;
+        for to in Square::A1..=Square::H8 {
+            write!(f, "{}, ", from.distance(to))?;
+        }
+        writeln!(f, "],")?;
+    }
+    writeln!(f, "];")?;
     Ok(())
 }
 
@@ -228,10 +238,11 @@ fn build_lines() -> Grid<Grid<Bitboard>> {
     };
     set_lines(RANK_1, North, 8);
     set_lines(FILE_A, East, 8);
-    set_lines(DIAG_A1_H8, NorthWest, 4);
-    set_lines(DIAG_A1_H8, SouthEast, 4);
-    set_lines(DIAG_A8_H1, NorthEast, 4);
-    set_lines(DIAG_A8_H1, SouthWest, 4);
+    // Shifting a diagonal vertically reaches every parallel diagonal.
+    set_lines(DIAG_A1_H8, North, 8);
+    set_lines(DIAG_A1_H8, South, 8);
+    set_lines(DIAG_A8_H1, North, 8);
+    set_lines(DIAG_A8_H1, South, 8);
     
     lines
 }
\ No newline at end of file
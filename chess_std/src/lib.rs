@@ -93,16 +93,29 @@ mod builder;
 pub mod board {
     pub use crate::position::{zobrist, Board};
     pub use crate::builder::Builder;
+    pub use crate::state::NullUndo;
 }
 
 mod movegen;
 pub use movegen::{MoveGenMasked, MoveGen, MoveGenerator};
 
+pub mod perft;
+
 mod game;
-pub use game::{Game, GameResult, WinType, DrawType};
+pub use game::{Game, GameResult, GameStatus, WinType, DrawType};
+
+#[cfg(feature = "pgn")]
+mod pgn;
 
 #[cfg(feature = "pgn")]
-pub use {moves::PGNMove, game::PGNTags};
+pub use {moves::PGNMove, game::{PGNTags, format_movetext}};
+#[cfg(feature = "pgn")]
+pub use pgn::{PGNTokenizer, PGNToken, Variation};
+
+#[cfg(feature = "eco")]
+mod eco;
+#[cfg(feature = "eco")]
+pub use eco::classify_eco;
 
 #[cfg(feature = "trees")]
-pub use game::{Tree, TreeNode, TreeIterator};
\ No newline at end of file
+pub use game::{Tree, TreeNode, TreeIterator, TreeDfsIterator};
\ No newline at end of file
@@ -1,8 +1,7 @@
 
 use crate::prelude::*;
 use crate::bit::{self, Bitboard};
-use crate::attack::{fill_line};
-use crate::position::{Board};
+use crate::position::{Board, Pieces};
 
 use arrayvec::ArrayVec;
 
@@ -39,7 +38,9 @@ pub trait MoveGenerator
 pub struct MoveGenMasked {
     quiets: QuietMoves,
     specials: SpecialMoves,
+    pieces: Pieces,
     orig_mask: Bitboard,
+    piece_mask: Bitboard,
     dest_mask: Bitboard,
     promotion_mask: Bitboard,
     promotion_index: usize
@@ -50,7 +51,13 @@ impl MoveGenMasked {
     // Whether a move is covered by the masks.
     #[inline]
     pub fn is_covered(&self, mv: Move) -> bool {
-        self.orig_mask.get(mv.from) && self.dest_mask.get(mv.to)
+        self.origins().get(mv.from) && self.dest_mask.get(mv.to)
+    }
+
+    // The origins allowed by both the origin and the piece masks.
+    #[inline]
+    fn origins(&self) -> Bitboard {
+        self.orig_mask & self.piece_mask
     }
 
     /// Restrict move iteration for given move origins.
@@ -64,14 +71,39 @@ impl MoveGenMasked {
     pub fn set_destination_mask(&mut self, dests: Bitboard) {
         self.dest_mask = dests;
     }
+
+    /// Restrict move iteration for given moving piece types.
+    /// This does NOT prevent from storing moves uncovered by the mask.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// // Both the knight and the pawn can capture, only the knight is kept.
+    /// let board = Board::from_fen("4k3/8/3r4/4p3/3P4/5N2/8/4K3 w - - 0 1").unwrap();
+    /// let mut gen = board.legal_captures();
+    /// gen.set_piece_mask(&[Knight]);
+    /// assert_eq!(gen.len(), 1);
+    /// assert_eq!(gen.next(), Some(Move::quiet(Square::F3, Square::E5)));
+    /// 
+    /// // Composes with the destination mask.
+    /// let mut gen = board.legal_moves_to(Square::E5);
+    /// gen.set_piece_mask(&[Bishop, Rook]);
+    /// assert_eq!(gen.len(), 0);
+    /// ```
+    pub fn set_piece_mask(&mut self, ptypes: &[PieceType]) {
+        self.piece_mask = ptypes.iter()
+            .fold(bit::EMPTY, |mask, ptype| mask | self.pieces[ptype.index()]);
+    }
 }
 
 impl Iterator for MoveGenMasked {
     type Item = Move;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let origins = self.origins();
         if let Some(of_piece) = self.quiets.first_mut() {
-            if !self.orig_mask.get(of_piece.from) {
+            if !origins.get(of_piece.from) {
                 // Skip the moves from here
                 self.quiets.remove(0);
                 self.next()
@@ -85,13 +117,13 @@ impl Iterator for MoveGenMasked {
                 } else if self.promotion_mask.get(of_piece.from) {
                     // Enumerate all the promotions from this piece
                     self.promotion_index += 1;
-                    if self.promotion_index >= 4 {
+                    if self.promotion_index > 4 {
                         // No more promotion in this destination, get another
                         self.promotion_index = 0;
                         of_piece.moves.remove(to);
                         self.next()
                     } else {
-                        let ptype = ALL_PIECE_TYPES[self.promotion_index + 1];
+                        let ptype = ALL_PIECE_TYPES[self.promotion_index];
                         Some(Move::promotion(of_piece.from, to, ptype))
                     }
                 } else {
@@ -117,7 +149,7 @@ impl ExactSizeIterator for MoveGenMasked {
     fn len(&self) -> usize {
         let mut n: usize = 0;
         for of_piece in &self.quiets {
-            if self.orig_mask.get(of_piece.from) {
+            if self.origins().get(of_piece.from) {
                 n += (of_piece.moves & self.dest_mask).pop_count() as usize
                     * if self.promotion_mask.get(of_piece.from) { 4 } else { 1 };
             }
@@ -130,12 +162,13 @@ impl ExactSizeIterator for MoveGenMasked {
 impl MoveGenerator for MoveGenMasked {
 
     fn contains(&self, mv: Move) -> bool {
-        if let Quiet = mv.flag {
-            self.quiets.iter()
-                .any(|of_pc| of_pc.from == mv.from && of_pc.moves.get(mv.to))
-        } else {
-            self.specials.iter()
-                .any(|mv2| *mv2 == mv)
+        match mv.flag {
+            Quiet => !self.promotion_mask.get(mv.from) && self.quiets.iter()
+                .any(|of_pc| of_pc.from == mv.from && of_pc.moves.get(mv.to)),
+            Promotion(ptype) => ptype.can_be_promotion()
+                && self.promotion_mask.get(mv.from) && self.quiets.iter()
+                .any(|of_pc| of_pc.from == mv.from && of_pc.moves.get(mv.to)),
+            _ => self.specials.iter().any(|mv2| mv2.matches(mv))
         }
     }
 }
@@ -145,7 +178,9 @@ impl From<MoveGen> for MoveGenMasked {
         Self {
             quiets: gen.quiets,
             specials: gen.specials,
+            pieces: gen.pieces,
             orig_mask: bit::FULL,
+            piece_mask: bit::FULL,
             dest_mask: bit::FULL,
             promotion_mask: gen.promotion_mask,
             promotion_index: gen.promotion_index
@@ -159,6 +194,7 @@ impl From<MoveGen> for MoveGenMasked {
 pub struct MoveGen {
     quiets: QuietMoves,
     specials: SpecialMoves,
+    pieces: Pieces,
     promotion_mask: Bitboard,
     promotion_index: usize
 }
@@ -169,6 +205,7 @@ impl MoveGen {
         MoveGen {  
             quiets: QuietMoves::new(),
             specials: SpecialMoves::new(),
+            pieces: [bit::EMPTY; NUM_PIECE_TYPES],
             promotion_mask: bit::EMPTY,
             promotion_index: 0
         }
@@ -179,6 +216,9 @@ impl MoveGen {
         use crate::attack::*;
 
         let mut gen = Self::new();
+        for ptype in &ALL_PIECE_TYPES {
+            gen.pieces[ptype.index()] = board.own_piece_type(*ptype);
+        }
         
         let from = board.king_square();
         let mut king_legals = bit::EMPTY;
@@ -189,7 +229,7 @@ impl MoveGen {
         }
         gen.add_moves_from(from, king_legals);
 
-        if  board.checkers.pop_count() < 2 {
+        if !board.is_double_check() {
             gen.add_non_king_moves(board);
             gen.add_castlings(board, board.king_square());
         }
@@ -197,24 +237,32 @@ impl MoveGen {
         gen
     }
 
+    /// Create a new generator from the legal en passant captures of a board only.
+    pub(crate) fn en_passants_of(board: &Board) -> Self {
+        let mut gen = Self::new();
+        if let Some(sq) = board.ep_target {
+            if !board.is_double_check() {
+                gen.add_en_passant(board, sq, Self::non_king_dests(board));
+            }
+        }
+        gen
+    }
+
+    // The destinations squares where we can get out of check, if any.
+    #[inline(always)]
+    fn non_king_dests(board: &Board) -> Bitboard {
+        if board.checkers.pop_count() == 1 {
+            board.check_blocking_squares()
+        } else {
+            bit::FULL
+        }
+    }
+
     // Add the moves from other pieces than the king.
     #[inline(always)]
     fn add_non_king_moves(&mut self, board: &Board) {
         use crate::attack::*;
-        // The destinations squares where we can get out of check, if any.
-        let mut dests = bit::FULL;
-        if board.checkers.pop_count() == 1 {
-            let checker = board.checkers.scan_forward();
-            // by capture...
-            dests = board.checkers;
-            let sliders = board.opponent_piece_type(Bishop)
-                        | board.opponent_piece_type(Rook)
-                        | board.opponent_piece_type(Queen);
-            if sliders.get(checker) {
-                // ...or by obstructing the sliding piece.
-                dests |= fill_between(board.king_square(), checker);
-            }
-        }
+        let dests = Self::non_king_dests(board);
         let ours = board.own_color();
         let enemy = board.opponent_color();
         for from in board.own_piece_type(Pawn) {
@@ -253,22 +301,26 @@ impl MoveGen {
     #[inline(always)]
     fn add_en_passant(&mut self, board: &Board, ep_target: Square, dests: Bitboard) {
         use crate::Direction::{self, *};
-        if !dests.get(ep_target) {
-            return;
-        }
+        use crate::attack::*;
         let passed = ep_target.shift(Direction::of_pawns(board.turn.opponent()));
-        if board.is_pinned(passed) && board.king_square().file() != passed.file() {
-            // The en passant capture is pinned on a rank/diagonal
-            // and the capturer would leave the check by not being pinned.
+        if !dests.get(ep_target) && !dests.get(passed) {
+            // Neither blocks nor captures the checker.
             return;
         }
+        let ksq = board.king_square();
+        let diagonals = board.opponent_piece_type(Bishop) | board.opponent_piece_type(Queen);
+        let lines = board.opponent_piece_type(Rook) | board.opponent_piece_type(Queen);
         // Convert to bitboard in case of edge overflowing
         let mut bb = bit::single(passed);
         bb = bb.shift(West) | bb.shift(East);
         for from in bb & board.own_piece_type(Pawn) {
-            let pin_ray = fill_line(from, board.king_square());
-            if !board.is_pinned(from) || pin_ray.get(ep_target) {
-                // Does not outpin
+            // Two pawns leave the same rank at once, so look for sliders
+            // from the king with the occupancy after the capture.
+            let occupied = board.occupied()
+                ^ bit::single(from) ^ bit::single(passed) ^ bit::single(ep_target);
+            let exposed = (of_bishop(ksq, bit::EMPTY, occupied) & diagonals)
+                        | (of_rook(ksq, bit::EMPTY, occupied) & lines);
+            if exposed.is_empty() {
                 self.add_special_move(Move::en_passant(from, ep_target, passed));
             }
         }
@@ -311,8 +363,8 @@ impl MoveGen {
     // out of their pin direction.
     fn add_non_outpinning_attacks(&mut self, board: &Board,
                                   from: Square, mut attacks: Bitboard) {
-        if board.is_pinned(from) {
-            attacks &= fill_line(from, board.king_square());
+        if let Some(ray) = board.pin_ray(from) {
+            attacks &= ray;
         }
         if attacks.is_populated() {
             self.add_moves_from(from, attacks);
@@ -321,8 +373,8 @@ impl MoveGen {
 
     fn add_non_outpinning_promotions(&mut self, board: &Board,
                                      from: Square, mut proms: Bitboard) {
-        if board.is_pinned(from) {
-            proms &= fill_line(from, board.king_square());
+        if let Some(ray) = board.pin_ray(from) {
+            proms &= ray;
         }
         if proms.is_populated() {
             self.add_promotion_from(from, proms);
@@ -404,11 +456,12 @@ impl MoveGenerator for MoveGen {
 
     fn contains(&self, mv: Move) -> bool {
         match mv.flag {
-            Quiet => self.quiets.iter()
+            Quiet => !self.promotion_mask.get(mv.from) && self.quiets.iter()
                 .any(|of_pc| of_pc.from == mv.from && of_pc.moves.get(mv.to)),
-            Promotion(ptype) => ptype.can_be_promotion() && self.quiets.iter()
+            Promotion(ptype) => ptype.can_be_promotion()
+                && self.promotion_mask.get(mv.from) && self.quiets.iter()
                 .any(|of_pc| of_pc.from == mv.from && of_pc.moves.get(mv.to)),
-            _ => self.specials.iter().any(|mv2| *mv2 == mv)
+            _ => self.specials.iter().any(|mv2| mv2.matches(mv))
         }
     }
 }
@@ -4,6 +4,7 @@
 */
 
 use crate::prelude::*;
+use crate::position::Board;
 pub use MoveFlag::*;
 
 
@@ -46,6 +47,11 @@ pub mod castling {
         pub(crate) fn index(self) -> usize {
             self as usize
         }
+
+        /// The side of an index, or `None` when out of range.
+        pub fn from_index(i: usize) -> Option<Side> {
+            [Side::King, Side::Queen].get(i).copied()
+        }
     }
 
     // The castling rights for a player.
@@ -68,7 +74,10 @@ pub enum MoveFlag {
 
 
 /// A minimal move information.
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+/// 
+/// It displays in coordinate notation (see `Move::uci`),
+/// and its debug form shows the flag as well.
+#[derive(PartialEq, Eq, Clone, Copy)]
 pub struct Move {
     pub from: Square,
     pub to: Square,
@@ -163,6 +172,28 @@ impl Move {
         *self == Self::NONE
     }
 
+    /// The same as `==`, except that two en passant captures match when
+    /// their origins and destinations do, whatever their passed squares:
+    /// the passed square can be derived from the position.
+    /// 
+    /// ```
+    /// use chess_std::{Square, Move};
+    /// 
+    /// let mv = Move::en_passant(Square::E5, Square::D6, Square::D5);
+    /// let from_gui = Move::en_passant(Square::E5, Square::D6, Square::D6);
+    /// assert_ne!(mv, from_gui);
+    /// assert!(mv.matches(from_gui));
+    /// assert!(!mv.matches(Move::quiet(Square::E5, Square::D6)));
+    /// ```
+    #[inline]
+    pub fn matches(&self, other: Move) -> bool {
+        match (self.flag, other.flag) {
+            (EnPassant(_), EnPassant(_)) =>
+                self.from == other.from && self.to == other.to,
+            _ => *self == other
+        }
+    }
+
     /// A simple verification of double push nature.
     /// ```
     /// use chess_std::{Color, Square, Move};
@@ -176,6 +207,65 @@ impl Move {
         self.from.shift(dir).shift(dir) == self.to
     }
 
+    /// Whether the move is a promotion, into any piece type.
+    /// ```
+    /// use chess_std::prelude::*;
+    /// assert!(Move::promotion(Square::B7, Square::B8, Knight).is_promotion());
+    /// assert!(!Move::quiet(Square::B7, Square::B8).is_promotion());
+    /// ```
+    #[inline]
+    pub fn is_promotion(&self) -> bool {
+        matches!(self.flag, Promotion(_))
+    }
+
+    /// Whether the move is a castling, on either side.
+    /// ```
+    /// use chess_std::prelude::*;
+    /// assert!(Move::castling(Black, Side::Queen).is_castling());
+    /// assert!(!Move::quiet(Square::E8, Square::C8).is_castling());
+    /// ```
+    #[inline]
+    pub fn is_castling(&self) -> bool {
+        matches!(self.flag, Castling(_))
+    }
+
+    /// Whether the move is an en passant capture.
+    /// ```
+    /// use chess_std::prelude::*;
+    /// assert!(Move::en_passant(Square::E5, Square::D6, Square::D5).is_en_passant());
+    /// assert!(!Move::quiet(Square::E5, Square::D6).is_en_passant());
+    /// ```
+    #[inline]
+    pub fn is_en_passant(&self) -> bool {
+        matches!(self.flag, EnPassant(_))
+    }
+
+    /// The piece type a pawn promotes into, if the move is a promotion.
+    /// ```
+    /// use chess_std::prelude::*;
+    /// let mv = Move::promotion(Square::E7, Square::E8, Queen);
+    /// assert_eq!(mv.promotion_piece(), Some(Queen));
+    /// assert_eq!(Move::quiet(Square::E2, Square::E4).promotion_piece(), None);
+    /// ```
+    #[inline]
+    pub fn promotion_piece(&self) -> Option<PieceType> {
+        if let Promotion(ptype) = self.flag { Some(ptype) } else { None }
+    }
+
+    /// The move in the UCI long algebraic notation,
+    /// where a castling is written as a move of the king.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// 
+    /// assert_eq!(Move::quiet(Square::E2, Square::E4).uci(), "e2e4");
+    /// assert_eq!(Move::promotion(Square::B7, Square::B8, Knight).uci(), "b7b8n");
+    /// assert_eq!(Move::castling(Black, Side::King).uci(), "e8g8");
+    /// ```
+    pub fn uci(&self) -> String {
+        self.to_string()
+    }
+
     /// A fast sanity check, which does not take in account the position.
     pub fn is_valid(&self, col: Color) -> bool {
         if !(self.from != self.to &&
@@ -193,21 +283,135 @@ impl Move {
             _ => true
         }
     }
+
+    /// A move whose flag is checked to fit the moved piece and the position,
+    /// unlike the other constructors. The destination must be reachable
+    /// by the piece, but the king safety is left to `Board::is_move_legal`.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::from_fen("4k3/1P6/8/3pP3/8/8/8/R3K3 w Q - 0 1").unwrap();
+    /// assert!(Move::validated(Square::B7, Square::B8, Promotion(Queen), &board).is_ok());
+    /// assert!(Move::validated(Square::A1, Square::A8, Promotion(Queen), &board).is_err());
+    /// assert!(Move::validated(Square::B7, Square::B8, Promotion(Pawn), &board).is_err());
+    /// assert!(Move::validated(Square::B7, Square::B8, Quiet, &board).is_err());
+    /// // No en passant target, as the last move was not d7-d5.
+    /// assert!(Move::validated(Square::E5, Square::D6, EnPassant(Square::D5), &board).is_err());
+    /// assert!(Move::validated(Square::E1, Square::C1, Castling(Side::Queen), &board).is_ok());
+    /// assert!(Move::validated(Square::E1, Square::G1, Castling(Side::King), &board).is_err());
+    /// ```
+    pub fn validated(from: Square, to: Square, flag: MoveFlag, board: &Board) ->
+                     Result<Move, String> {
+        let mv = Move{ from, to, flag };
+        let col = board.turn;
+        let pc = board.piece_at(from)
+            .ok_or_else(|| format!("No piece to move on {}", from))?;
+        if pc.color != col {
+            return Err(format!("The piece on {} cannot move this turn", from));
+        }
+        match flag {
+            Quiet => {
+                if pc.ptype == Pawn && to.rank() == Rank::last(col) {
+                    return Err("A pawn reaching the last rank must promote".to_owned());
+                }
+            }
+            Promotion(ptype) => {
+                if pc.ptype != Pawn {
+                    return Err(format!("Only a pawn can promote, not a {}", pc.ptype));
+                }
+                if !ptype.can_be_promotion() {
+                    return Err(format!("Inadequate piece type for promotion: {}", ptype));
+                }
+                if to.rank() != Rank::last(col) {
+                    return Err("A promotion must reach the last rank".to_owned());
+                }
+            }
+            // The passed square is implied by the target.
+            EnPassant(_) => {
+                if pc.ptype != Pawn {
+                    return Err(format!("Only a pawn can capture en passant, not a {}", pc.ptype));
+                }
+                if board.ep_target != Some(to) {
+                    return Err(format!("No en passant target on {}", to));
+                }
+            }
+            Castling(side) => {
+                if mv != Self::castling(col, side) {
+                    return Err(format!("Not a castling for {}: {}", col, mv.verbose()));
+                }
+                if !board.has_right(col, side) {
+                    return Err("The castling right is lost".to_owned());
+                }
+            }
+        }
+        if !board.is_pseudo_legal(mv) {
+            return Err(format!("Impossible move in this position: {}", mv.verbose()));
+        }
+        Ok(mv)
+    }
+
+    /// The detailed form of the move, with its flag.
+    /// ```
+    /// use chess_std::{Square, Move};
+    /// 
+    /// let mv = Move::quiet(Square::E2, Square::E4);
+    /// assert_eq!(mv.verbose(), "Move(e2, e4, Quiet)");
+    /// ```
+    pub fn verbose(&self) -> String {
+        format!("{:?}", self)
+    }
 }
 
 use std::fmt;
 
+/// The coordinate notation of the move, which does not need the position.
+/// A castling is a move of the king, and the null move is `0000`.
+/// 
+/// ```
+/// use chess_std::prelude::*;
+/// 
+/// assert_eq!(Move::quiet(Square::E2, Square::E4).to_string(), "e2e4");
+/// assert_eq!(Move::promotion(Square::E7, Square::E8, Queen).to_string(), "e7e8q");
+/// assert_eq!(Move::en_passant(Square::E5, Square::D6, Square::D5).to_string(), "e5d6");
+/// assert_eq!(Move::castling(White, Side::King).to_string(), "e1g1");
+/// assert_eq!(Move::castling(Black, Side::Queen).to_string(), "e8c8");
+/// assert_eq!(Move::NONE.to_string(), "0000");
+/// ```
 impl fmt::Display for Move {
     fn fmt(&self, ft: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(ft, "Move({}, {}, {:?})",
-            self.from.san(),
-            self.to.san(),
-            self.flag
-        )?;
+        if self.is_none() {
+            return write!(ft, "0000");
+        }
+        write!(ft, "{}{}", self.from, self.to)?;
+        if let Some(new) = self.promotion_piece() {
+            write!(ft, "{}", new.to_char().to_ascii_lowercase())?;
+        }
         Ok(())
     }
 }
 
+/// The origin, the destination and the flag of the move.
+/// 
+/// ```
+/// use chess_std::prelude::*;
+/// 
+/// let mv = Move::promotion(Square::E7, Square::E8, Queen);
+/// assert_eq!(format!("{:?}", mv), "Move(e7, e8, Promotion(Queen))");
+/// let mv = Move::en_passant(Square::E5, Square::D6, Square::D5);
+/// assert_eq!(format!("{:?}", mv), "Move(e5, d6, EnPassant(d5))");
+/// assert_eq!(format!("{:?}", Move::NONE), "Move::NONE");
+/// ```
+impl fmt::Debug for Move {
+    fn fmt(&self, ft: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_none() {
+            return write!(ft, "Move::NONE");
+        }
+        write!(ft, "Move({:?}, {:?}, {:?})", self.from, self.to, self.flag)
+    }
+}
+
 
 
 /// The status of king, whether it may be check or even checkmate.
@@ -233,7 +437,7 @@ impl fmt::Display for CheckType {
 /// the capture of the move and eventual check/checkmate.
 /// It does not supports annotations though.
 #[cfg(feature = "pgn")]
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub struct PGNMove {
     pub ptype: PieceType,
     pub from: Square,
@@ -270,33 +474,38 @@ impl From<PGNMove> for Move {
     }
 }
 
-/// The long SAN notation.
+/// The standard algebraic notation, without the origin of a piece when
+/// another one of the same type could reach the destination.
+/// See `Board::to_san` for the disambiguated notation.
+/// 
+/// ```
+/// use chess_std::prelude::*;
+/// use chess_std::{PGNMove, CheckType};
+/// 
+/// let mv = Move::quiet(Square::G1, Square::F3);
+/// let pgn_mv = PGNMove::from_plain(mv, Knight, None, CheckType::None);
+/// assert_eq!(pgn_mv.to_string(), "Nf3");
+/// let mv = Move::promotion(Square::E7, Square::D8, Queen);
+/// let pgn_mv = PGNMove::from_plain(mv, Pawn, Some(Rook), CheckType::Check);
+/// assert_eq!(pgn_mv.to_string(), "exd8=Q+");
+/// ```
 #[cfg(feature = "pgn")]
 impl fmt::Display for PGNMove {
     fn fmt(&self, ft: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let (from_s, to_s) = (self.from.san(), self.to.san());
-        let pc_s = if self.ptype != Pawn {
-            self.ptype.to_char().to_string()
-        } else {
-            String::new()
+        let to_s = self.to.san();
+        let is_capture = self.capture.is_some() || matches!(self.flag, EnPassant(_));
+        let move_s = match self.flag {
+            Castling(Side::King)  => "O-O".to_owned(),
+            Castling(Side::Queen) => "O-O-O".to_owned(),
+            _ if self.ptype != Pawn => format!(
+                "{}{}{}", self.ptype.to_char(), if is_capture { "x" } else { "" }, to_s),
+            _ if is_capture => format!("{}x{}", self.from.file().to_char(), to_s),
+            _ => to_s,
         };
-        let cap_s = if self.capture.is_some() { "x" } else { "" };
-        write!(
-            ft, "{}{}",
-            match self.flag {
-                Quiet =>
-                    format!("{}{}{}{}", pc_s, self.from.san(), cap_s, to_s),
-                EnPassant(_) =>
-                    format!("{}x{}e.p.", from_s, to_s),
-                Promotion(new) =>
-                    format!("{}{}{}={}", from_s, cap_s, to_s, new.to_char()),
-                Castling(side) => match side {
-                    Side::King  => "O-O".to_owned(),
-                    Side::Queen => "O-O-O".to_owned(),
-                },
-            },
-            self.check
-        )?;
-        Ok(())
+        write!(ft, "{}", move_s)?;
+        if let Promotion(new) = self.flag {
+            write!(ft, "={}", new.to_char())?;
+        }
+        write!(ft, "{}", self.check)
     }
 }
\ No newline at end of file
@@ -0,0 +1,92 @@
+/* Count the leaf nodes of the legal move tree, to verify move generation.
+*/
+
+use std::collections::HashMap;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::position::Board;
+use crate::moves::Move;
+
+
+/// The number of leaf nodes at a depth from a board.
+/// The last level is counted in bulk with `Board::num_moves`.
+/// 
+/// ```
+/// use chess_std::{Board, perft};
+/// 
+/// assert_eq!(perft::perft(&Board::new(), 0), 1);
+/// assert_eq!(perft::perft(&Board::new(), 3), 8902);
+/// ```
+pub fn perft(board: &Board, depth: u32) -> u64 {
+    match depth {
+        0 => 1,
+        1 => board.num_moves() as u64,
+        _ => board.legal_moves()
+            .map(|mv| perft(&board.play_move(mv), depth - 1))
+            .sum()
+    }
+}
+
+/// The leaf nodes at a depth under each legal move of a board,
+/// to locate a diverging move against another move generator.
+/// 
+/// ```
+/// use chess_std::{Board, perft};
+/// 
+/// let board = Board::new();
+/// let counts = perft::divide(&board, 3);
+/// assert_eq!(counts.len(), 20);
+/// assert_eq!(counts.iter().map(|(_, n)| n).sum::<u64>(), perft::perft(&board, 3));
+/// ```
+pub fn divide(board: &Board, depth: u32) -> Vec<(Move, u64)> {
+    board.legal_moves()
+        .map(|mv| (mv, perft(&board.play_move(mv), depth.saturating_sub(1))))
+        .collect()
+}
+
+/// The same as `perft`, with the subtree counts cached in `tt` by the
+/// zobrist hash of the board and the remaining depth, so that the
+/// transpositions are only counted once.
+/// 
+/// ```
+/// use std::collections::HashMap;
+/// use chess_std::{Board, perft};
+/// 
+/// let board = Board::new();
+/// assert_eq!(perft::perft_hashed(&board, 4, &mut HashMap::new()), perft::perft(&board, 4));
+/// ```
+pub fn perft_hashed(board: &Board, depth: u32, tt: &mut HashMap<(u64, u32), u64>) -> u64 {
+    if depth <= 1 {
+        return perft(board, depth);
+    }
+    let key = (board.zobrist_hash(), depth);
+    if let Some(&n) = tt.get(&key) {
+        return n;
+    }
+    let n = board.legal_moves()
+        .map(|mv| perft_hashed(&board.play_move(mv), depth - 1, tt))
+        .sum();
+    tt.insert(key, n);
+    n
+}
+
+/// The same as `perft`, with the root moves split across the rayon thread pool.
+/// 
+/// ```
+/// use chess_std::{Board, perft};
+/// 
+/// let board = Board::new();
+/// assert_eq!(perft::perft_parallel(&board, 4), perft::perft(&board, 4));
+/// ```
+#[cfg(feature = "parallel")]
+pub fn perft_parallel(board: &Board, depth: u32) -> u64 {
+    if depth <= 1 {
+        return perft(board, depth);
+    }
+    let moves: Vec<_> = board.legal_moves().collect();
+    moves.par_iter()
+        .map(|&mv| perft(&board.play_move(mv), depth - 1))
+        .sum()
+}
@@ -0,0 +1,258 @@
+/* A tokenizer for the PGN format, which reads the text incrementally
+   so that large databases need not be matched as a whole.
+*/
+
+use crate::prelude::*;
+
+
+/// A bracket of a recursive annotation variation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variation {
+    Open,
+    Close
+}
+
+/// A lexical unit of PGN. The strings are borrowed from the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PGNToken<'a> {
+    /// A tag name and its unquoted value, such as `[Event "Casual"]`.
+    /// The escapes `\"` and `\\` of the value are kept as written.
+    TagPair(&'a str, &'a str),
+    /// A move number, followed by a White move (`12.`)
+    /// or by a Black move (`12...`).
+    MoveNumber(u32, Color),
+    /// A move in SAN, without its suffix annotation.
+    San(&'a str),
+    /// The trimmed text of a `{...}` or `;` comment.
+    Comment(&'a str),
+    Variation(Variation),
+    /// A numeric annotation glyph, either written as `$n`
+    /// or as a suffix annotation such as `!?`.
+    Nag(u8),
+    /// A game termination marker: `1-0`, `0-1`, `1/2-1/2` or `*`.
+    Result(&'a str),
+}
+
+/// An iterator over the tokens of PGN text.
+/// Characters that do not form any token are skipped.
+///
+/// ```
+/// use chess_std::{Color, PGNTokenizer, PGNToken::*};
+///
+/// let tokens: Vec<_> = PGNTokenizer::new("[Round \"1\"] 1. e4 {Best by test} e5?! 1-0").collect();
+/// assert_eq!(tokens, vec![
+///     TagPair("Round", "1"),
+///     MoveNumber(1, Color::White),
+///     San("e4"),
+///     Comment("Best by test"),
+///     San("e5"),
+///     Nag(6),
+///     Result("1-0"),
+/// ]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PGNTokenizer<'a> {
+    pgn: &'a str,
+    pos: usize,
+    // The annotation of the last SAN token.
+    pending: Option<PGNToken<'a>>
+}
+
+impl<'a> PGNTokenizer<'a> {
+    /// A tokenizer from the start of a PGN text.
+    pub fn new(pgn: &'a str) -> Self {
+        Self{ pgn, pos: 0, pending: None }
+    }
+
+    // The rest of the text, until a character or the end.
+    // The position is moved after that character.
+    fn take_until(&mut self, end: char) -> &'a str {
+        let rest = &self.pgn[self.pos..];
+        match rest.find(end) {
+            Some(i) => {
+                self.pos += i + end.len_utf8();
+                &rest[..i]
+            }
+            None => {
+                self.pos = self.pgn.len();
+                rest
+            }
+        }
+    }
+
+    // A tag pair, after its opening bracket. The value is a quoted string
+    // which may hold brackets and escaped quotes, so the pair only ends
+    // at the bracket after the closing quote.
+    fn tag_pair(&mut self) -> PGNToken<'a> {
+        let rest = &self.pgn[self.pos..];
+        let begin = rest.len() - rest.trim_start().len();
+        let len = rest[begin..]
+            .find(|c: char| c.is_whitespace() || c == '"' || c == ']')
+            .unwrap_or(rest.len() - begin);
+        let tag = &rest[begin..begin + len];
+        self.pos += begin + len;
+
+        let rest = &self.pgn[self.pos..];
+        let value = match rest.trim_start().strip_prefix('"') {
+            Some(quoted) => {
+                let mut end = quoted.len();
+                let mut escaped = false;
+                for (i, c) in quoted.char_indices() {
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => { end = i; break; }
+                        _ => {}
+                    }
+                }
+                self.pos += rest.len() - quoted.len() + end;
+                &quoted[..end]
+            }
+            None => self.take_until_peek(']').trim(),
+        };
+        self.take_until(']');
+        PGNToken::TagPair(tag, value)
+    }
+
+    // The rest of the text, until a character or the end,
+    // leaving the position on that character.
+    fn take_until_peek(&mut self, end: char) -> &'a str {
+        let rest = &self.pgn[self.pos..];
+        let i = rest.find(end).unwrap_or(rest.len());
+        self.pos += i;
+        &rest[..i]
+    }
+
+    // The NAG of a suffix annotation.
+    fn suffix_nag(suffix: &str) -> Option<u8> {
+        match suffix {
+            "!"  => Some(1),
+            "?"  => Some(2),
+            "!!" => Some(3),
+            "??" => Some(4),
+            "!?" => Some(5),
+            "?!" => Some(6),
+            _    => None
+        }
+    }
+
+    // A token that starts neither a tag, a comment nor a variation.
+    fn symbol(&mut self, word: &'a str) -> Option<PGNToken<'a>> {
+        if matches!(word, "1-0" | "0-1" | "1/2-1/2" | "*") {
+            return Some(PGNToken::Result(word));
+        }
+        if let Some(nag) = word.strip_prefix('$') {
+            return nag.parse().ok().map(PGNToken::Nag);
+        }
+        let digits = word.find(|c: char| !c.is_ascii_digit()).unwrap_or(word.len());
+        if digits > 0 && word[digits..].starts_with('.') {
+            // A move may be glued to its number, as in `1.e4`.
+            let dots = word[digits..].find(|c| c != '.').unwrap_or(word.len() - digits);
+            self.pos -= word.len() - digits - dots;
+            let col = if dots >= 3 { Black } else { White };
+            return word[..digits].parse().ok().map(|n| PGNToken::MoveNumber(n, col));
+        }
+        let san = word.trim_end_matches(['!', '?']);
+        self.pending = Self::suffix_nag(&word[san.len()..]).map(PGNToken::Nag);
+        if san.is_empty() {
+            self.pending.take()
+        } else {
+            Some(PGNToken::San(san))
+        }
+    }
+}
+
+impl<'a> Iterator for PGNTokenizer<'a> {
+    type Item = PGNToken<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(token) = self.pending.take() {
+            return Some(token);
+        }
+        loop {
+            let rest = &self.pgn[self.pos..];
+            let start = rest.find(|c: char| !c.is_whitespace())?;
+            self.pos += start;
+            let c = rest[start..].chars().next()?;
+            self.pos += c.len_utf8();
+            let token = match c {
+                '[' => Some(self.tag_pair()),
+                '{' => Some(PGNToken::Comment(self.take_until('}').trim())),
+                ';' => Some(PGNToken::Comment(self.take_until('\n').trim())),
+                '(' => Some(PGNToken::Variation(Variation::Open)),
+                ')' => Some(PGNToken::Variation(Variation::Close)),
+                ']' | '}' => None,
+                _ => {
+                    let pgn = self.pgn;
+                    let begin = self.pos - c.len_utf8();
+                    let len = pgn[begin..]
+                        .find(|c: char| c.is_whitespace() || "[]{}();".contains(c))
+                        .unwrap_or(pgn.len() - begin);
+                    self.pos = begin + len;
+                    self.symbol(&pgn[begin..begin + len])
+                }
+            };
+            if token.is_some() {
+                return token;
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tokenizer_test {
+    use super::*;
+    use PGNToken::*;
+
+    #[test]
+    fn test_tokens() {
+        let pgn = "[Event \"Casual game\"]\n\
+                   [FEN \"8/8/8/8/8/8/8/k1K5 w - - 0 1\"]\n\
+                   \n\
+                   1.e4 $1 e5 {Open game} (1... c5!? ; Sicilian\n\
+                   2. Nf3 (2.c3 d5) d6) 2. Nf3 Nc6?? 3. Bb5 1/2-1/2";
+        let tokens: Vec<_> = PGNTokenizer::new(pgn).collect();
+        assert_eq!(tokens, vec![
+            TagPair("Event", "Casual game"),
+            TagPair("FEN", "8/8/8/8/8/8/8/k1K5 w - - 0 1"),
+            MoveNumber(1, White), San("e4"), Nag(1),
+            San("e5"), Comment("Open game"),
+            Variation(super::Variation::Open),
+            MoveNumber(1, Black), San("c5"), Nag(5), Comment("Sicilian"),
+            MoveNumber(2, White), San("Nf3"),
+            Variation(super::Variation::Open),
+            MoveNumber(2, White), San("c3"), San("d5"),
+            Variation(super::Variation::Close),
+            San("d6"),
+            Variation(super::Variation::Close),
+            MoveNumber(2, White), San("Nf3"), San("Nc6"), Nag(4),
+            MoveNumber(3, White), San("Bb5"),
+            Result("1/2-1/2"),
+        ]);
+    }
+
+    #[test]
+    fn test_tag_value_brackets() {
+        let pgn = r#"[Event "Rated [Blitz] game"] [Annotator "\"Fritz\" \\ 17"] [Round -] 1. e4 *"#;
+        let tokens: Vec<_> = PGNTokenizer::new(pgn).collect();
+        assert_eq!(tokens, vec![
+            TagPair("Event", "Rated [Blitz] game"),
+            TagPair("Annotator", r#"\"Fritz\" \\ 17"#),
+            TagPair("Round", "-"),
+            MoveNumber(1, White), San("e4"),
+            Result("*"),
+        ]);
+    }
+
+    #[test]
+    fn test_unterminated() {
+        let tokens: Vec<_> = PGNTokenizer::new("1. e4 {Unfinished").collect();
+        assert_eq!(tokens, vec![MoveNumber(1, White), San("e4"), Comment("Unfinished")]);
+        let tokens: Vec<_> = PGNTokenizer::new("[Event").collect();
+        assert_eq!(tokens, vec![TagPair("Event", "")]);
+        let tokens: Vec<_> = PGNTokenizer::new("[Event \"Casual").collect();
+        assert_eq!(tokens, vec![TagPair("Event", "Casual")]);
+        assert_eq!(PGNTokenizer::new(" \n ").next(), None);
+    }
+}
@@ -76,18 +76,25 @@ pub struct Board {
     pub(crate) hash: zobrist::Hash,              // Positional hash
     pub turn: Color,
 
-    pub half_move_clock: u32,
+    pub half_move_clock: u32,                    // Plies since a capture or pawn move
     pub(crate) ep_target: Option<Square>,
     pub(crate) rights: PlayersRights,
-    pub(crate) last_cap_or_push: u32,            // As a move index
+    pub(crate) fullmove_number: u32,             // Starts at 1, after Black plays
 
     pub(crate) checkers: Bitboard,               // Currently checking pieces
     pub(crate) pinned: Bitboard,                 // Currently pinned pieces
+    pub(crate) pinners: Bitboard,                // Opponent sliders pinning them
 }
 
 /// Some piece/bitboard manipulation functions.
 impl Board {
 
+    /// The game phase of the starting position.
+    pub const MAX_PHASE: u8 = 24;
+
+    // The non-pawn material of both players in the starting position.
+    const START_NON_PAWN_MATERIAL: u32 = 62;
+
     /// Returns the bitboard of a player.
     #[inline]
     pub fn color(&self, col: Color) -> Bitboard {
@@ -136,6 +143,166 @@ impl Board {
         self.color(col) & self.piece_type(ptype)
     }
 
+    /// The number of pieces of a color and a type on the board.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::new();
+    /// assert_eq!(board.count(W_PAWN), 8);
+    /// assert_eq!(board.count(B_QUEEN), 1);
+    /// ```
+    #[inline]
+    pub fn count(&self, pc: Piece) -> u32 {
+        self.piece(pc).pop_count()
+    }
+
+    /// The number of pieces of a type on the board, for both players.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// assert_eq!(Board::new().count_type(Knight), 4);
+    /// ```
+    #[inline]
+    pub fn count_type(&self, ptype: PieceType) -> u32 {
+        self.piece_type(ptype).pop_count()
+    }
+
+    /// Whether at least one such piece is on the board.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// assert!(Board::new().has_piece(W_QUEEN));
+    /// assert!(!Board::default().has_piece(B_KING));
+    /// ```
+    #[inline]
+    pub fn has_piece(&self, pc: Piece) -> bool {
+        self.piece(pc).is_populated()
+    }
+
+    /// The value of the knights, bishops, rooks and queens of a player.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// assert_eq!(Board::new().non_pawn_material(White), 31);
+    /// ```
+    pub fn non_pawn_material(&self, col: Color) -> u32 {
+        [Knight, Bishop, Rook, Queen].iter()
+            .map(|ptype| self.count(Piece{ color: col, ptype: *ptype }) * ptype.value() as u32)
+            .sum()
+    }
+
+    /// The material of both players as in endgame tablebases, White first:
+    /// the uppercase letters of the pieces from the king to the pawns,
+    /// separated by a `v`.
+    /// 
+    /// ```
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::from_fen("8/4k3/3r4/8/2P5/8/4K3/R7 w - - 0 1").unwrap();
+    /// assert_eq!(board.material_signature(), "KRPvKR");
+    /// assert_eq!(Board::new().material_signature(), "KQRRBBNNPPPPPPPPvKQRRBBNNPPPPPPPP");
+    /// ```
+    pub fn material_signature(&self) -> String {
+        let side = |col: Color| -> String {
+            [King, Queen, Rook, Bishop, Knight, Pawn].iter()
+                .flat_map(|ptype| {
+                    let n = self.count(Piece{ color: col, ptype: *ptype }) as usize;
+                    std::iter::repeat_n(ptype.to_char(), n)
+                })
+                .collect()
+        };
+        format!("{}v{}", side(White), side(Black))
+    }
+
+    /// The game phase from 0, with kings and pawns only, to `Board::MAX_PHASE`
+    /// with all the starting pieces, proportional to the non-pawn material.
+    /// It allows to interpolate between a middlegame and an endgame evaluation.
+    pub fn game_phase(&self) -> u8 {
+        let material = self.non_pawn_material(White) + self.non_pawn_material(Black);
+        let phase = material * Self::MAX_PHASE as u32 / Self::START_NON_PAWN_MATERIAL;
+        phase.min(Self::MAX_PHASE as u32) as u8
+    }
+
+    /// The pawns of a player that share their file with another of its pawns.
+    /// 
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate chess_std;
+    /// use chess_std::prelude::*;
+    /// use chess_std::{bit, Board};
+    /// 
+    /// # fn main() {
+    /// let board = Board::from_fen("4k3/p5p1/8/4P3/3p4/1P6/1P4PP/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.doubled_pawns(White), merge_sq!(Square::B2, Square::B3));
+    /// assert_eq!(board.doubled_pawns(Black), bit::EMPTY);
+    /// # }
+    /// ```
+    pub fn doubled_pawns(&self, col: Color) -> Bitboard {
+        use crate::Direction::*;
+        let pawns = self.of_color_and_type(col, Pawn);
+        pawns & (pawns.shift(North).north_fill() | pawns.shift(South).south_fill())
+    }
+
+    /// The pawns of a player without any of its pawns on the adjacent files.
+    /// 
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate chess_std;
+    /// use chess_std::prelude::*;
+    /// use chess_std::{bit, Board};
+    /// 
+    /// # fn main() {
+    /// let board = Board::from_fen("4k3/p5p1/8/4P3/3p4/1P6/1P4PP/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.isolated_pawns(White), merge_sq!(Square::B2, Square::B3, Square::E5));
+    /// assert_eq!(board.isolated_pawns(Black), merge_sq!(Square::A7, Square::D4, Square::G7));
+    /// # }
+    /// ```
+    pub fn isolated_pawns(&self, col: Color) -> Bitboard {
+        use crate::Direction::*;
+        let pawns = self.of_color_and_type(col, Pawn);
+        let neighbors = (pawns.shift(East) | pawns.shift(West)).file_fill();
+        pawns & !neighbors
+    }
+
+    /// The pawns of a player that no enemy pawn can stop, being in front of them
+    /// on the same file or on an adjacent file.
+    /// 
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate chess_std;
+    /// use chess_std::prelude::*;
+    /// use chess_std::{bit, Board};
+    /// 
+    /// # fn main() {
+    /// let board = Board::from_fen("4k3/p5p1/8/4P3/3p4/1P6/1P4PP/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.passed_pawns(White), merge_sq!(Square::E5));
+    /// assert_eq!(board.passed_pawns(Black), merge_sq!(Square::D4));
+    /// 
+    /// // The pawn on d5 only stops the white pawns behind it.
+    /// let board = Board::from_fen("4k3/8/8/3pP3/8/8/2P5/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.passed_pawns(White), merge_sq!(Square::E5));
+    /// assert_eq!(board.passed_pawns(Black), bit::EMPTY);
+    /// # }
+    /// ```
+    pub fn passed_pawns(&self, col: Color) -> Bitboard {
+        use crate::Direction::*;
+        let enemy = self.of_color_and_type(col.opponent(), Pawn);
+        let front = match col {
+            White => enemy.shift(South).south_fill(),
+            Black => enemy.shift(North).north_fill(),
+        };
+        let stoppers = front | front.shift(East) | front.shift(West);
+        self.of_color_and_type(col, Pawn) & !stoppers
+    }
+
     /// Returns the bitboard of empty squares.
     #[inline]
     pub fn empty(&self) -> Bitboard {
@@ -205,22 +372,13 @@ impl Board {
     /// assert_eq!(board.piece_type_at(Square::E1), Some(PieceType::King));
     /// ```
     #[inline]
-    pub fn piece_type_at(&self, sq: Square) -> Option<PieceType> {    
+    pub fn piece_type_at(&self, sq: Square) -> Option<PieceType> {
         if !self.occupied().get(sq) {
-            None
-        } else if self.piece_type(Pawn).get(sq) {
-            Some(Pawn)
-        } else if self.piece_type(Knight).get(sq) {
-            Some(Knight)
-        } else if self.piece_type(Bishop).get(sq) {
-            Some(Bishop)
-        } else if self.piece_type(Rook).get(sq) {
-            Some(Rook)
-        } else if self.piece_type(Queen).get(sq) {
-            Some(Queen)
-        } else {
-            Some(King)
+            return None;
         }
+        self.pieces.iter()
+            .position(|bb| bb.get(sq))
+            .and_then(PieceType::from_index)
     }
 
     /// The piece at a square, if any.
@@ -261,6 +419,55 @@ impl Board {
         self.remove_piece(pc, from);
         self.add_piece(pc, to)
     }
+
+    /// Place a piece at an empty square, for position editing.
+    /// The position may not be valid afterwards, see `Board::is_valid`.
+    /// 
+    /// Returns an error if the square is occupied.
+    pub fn place(&mut self, pc: Piece, sq: Square) -> Result<(), String> {
+        if self.is_occupied(sq) {
+            return Err(format!("Square {} is occupied", sq.san()));
+        }
+        self.add_piece(pc, sq);
+        self.update_attacks();
+        Ok(())
+    }
+
+    /// Remove the piece at a square if any, for position editing.
+    /// The position may not be valid afterwards, see `Board::is_valid`.
+    pub fn clear(&mut self, sq: Square) {
+        if let Some(pc) = self.piece_at(sq) {
+            self.remove_piece(pc, sq);
+            self.update_attacks();
+        }
+    }
+
+    /// Set the side to move, for position editing.
+    /// The en passant target is dropped as it belongs to the previous turn.
+    pub fn set_turn(&mut self, col: Color) {
+        if self.turn != col {
+            self.turn = col;
+            self.ep_target = None;
+            self.update_attacks();
+        }
+    }
+
+    /// A copy of this board with another side to move, as with `Board::set_turn`.
+    /// The checkers and the pins are those of the new side to move,
+    /// and the zobrist hash includes the new turn.
+    /// 
+    /// ```
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+    /// assert!(board.in_check());
+    /// assert!(!board.with_turn(board.turn.opponent()).in_check());
+    /// ```
+    pub fn with_turn(&self, col: Color) -> Board {
+        let mut board = self.clone();
+        board.set_turn(col);
+        board
+    }
 }
 
 impl Default for Board {
@@ -276,10 +483,11 @@ impl Default for Board {
             half_move_clock: 0,
             ep_target: None,
             rights: ALL_PLAYERS_RIGHTS,
-            last_cap_or_push: 0,
+            fullmove_number: 1,
 
             checkers: bit::EMPTY,
             pinned: bit::EMPTY,
+            pinners: bit::EMPTY,
         };
         empty.rehash();
         empty
@@ -301,21 +509,34 @@ impl Board {
             half_move_clock: 0,
             ep_target: None,
             rights: ALL_PLAYERS_RIGHTS,
-            last_cap_or_push: 0,
+            fullmove_number: 1,
 
             checkers: bit::EMPTY,
             pinned: bit::EMPTY,
+            pinners: bit::EMPTY,
         }
     }
 
     /// Returns the number of moves played since the beginning of the game.
     pub fn num_moves_played(&self) -> u32 {
-        self.half_move_clock * 2 + match self.turn {
+        self.fullmove_number.saturating_sub(1) * 2 + match self.turn {
             White => 0,
             Black => 1,
         }
     }
 
+    /// The number of half-moves since the last capture or pawn move,
+    /// for the fifty-move rule. This is the fifth field of FEN.
+    pub fn halfmove_clock(&self) -> u32 {
+        self.half_move_clock
+    }
+
+    /// The number of the move pair being played, starting at 1 and incremented
+    /// after each Black move. This is the sixth field of FEN.
+    pub fn fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
     /// Get the pieces that check the current king.
     pub fn checkers(&self) -> Bitboard {
         self.checkers
@@ -326,6 +547,11 @@ impl Board {
         self.pinned
     }
 
+    /// Get the opponent sliders that pin a piece to the current king.
+    pub fn pinners(&self) -> Bitboard {
+        self.pinners
+    }
+
     /// Get the square where an en passant capture would be possible.
     pub fn en_passant_target(&self) -> Option<Square> {
         self.ep_target
@@ -392,6 +618,231 @@ impl Board {
         !self.is_attacked(sq, for_.opponent())
     }
 
+    /// The pieces of both colors that attack a square, with `occupied` as
+    /// the blockers of sliding pieces. Pieces outside of `occupied` are kept.
+    /// 
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate chess_std;
+    /// use chess_std::prelude::*;
+    /// use chess_std::{Board, bit};
+    /// 
+    /// # fn main() {
+    /// let board = Board::from_fen("4k3/8/8/3p4/8/5N2/8/3RK3 w - - 0 1").unwrap();
+    /// assert_eq!(board.attackers_to(Square::D5, board.occupied()), merge_sq!(Square::D1));
+    /// assert_eq!(board.attackers_to(Square::E4, board.occupied()), merge_sq!(Square::D5));
+    /// assert_eq!(board.attackers_to(Square::E5, board.occupied()), merge_sq!(Square::F3));
+    /// assert_eq!(board.attackers_to(Square::D8, board.occupied()),
+    ///            merge_sq!(Square::E8));
+    /// // Once the pawn is removed, the rook sees through the file.
+    /// let occupied = board.occupied() ^ bit::single(Square::D5);
+    /// assert_eq!(board.attackers_to(Square::D8, occupied),
+    ///            merge_sq!(Square::D1, Square::E8));
+    /// # }
+    /// ```
+    pub fn attackers_to(&self, sq: Square, occupied: Bitboard) -> Bitboard {
+        use crate::attack::*;
+        let queens = self.piece_type(Queen);
+        let pawns_of = |col: Color| self.piece_type(Pawn) & self.color(col);
+        (of_bishop(sq, bit::EMPTY, occupied) & (self.piece_type(Bishop) | queens))
+        | (of_rook(sq, bit::EMPTY, occupied) & (self.piece_type(Rook) | queens))
+        | (of_knight(sq, bit::EMPTY) & self.piece_type(Knight))
+        | (of_king(sq, bit::EMPTY) & self.piece_type(King))
+        | (of_pawn(Black, sq, bit::FULL) & pawns_of(White))
+        | (of_pawn(White, sq, bit::FULL) & pawns_of(Black))
+    }
+
+    /// The pieces of a color that attack a square when the blockers are
+    /// transparent, which reveals the sliders standing behind them.
+    /// The blockers themselves are left out, and with no blockers
+    /// these are the attackers of the color given by `Board::attackers_to`.
+    pub fn xray_attackers_to(&self, sq: Square, by: Color, blockers: Bitboard) -> Bitboard {
+        let occupied = self.occupied() & !blockers;
+        self.attackers_to(sq, occupied) & occupied & self.color(by)
+    }
+
+    /// The square and type of the least valuable piece of a color that
+    /// attacks a square, `None` when there is none. The king comes last.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// // The queen and the pawn both attack d5.
+    /// let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/3QK3 w - - 0 1").unwrap();
+    /// assert_eq!(board.smallest_attacker(Square::D5, White), Some((Square::E4, Pawn)));
+    /// assert_eq!(board.smallest_attacker(Square::E4, Black), Some((Square::D5, Pawn)));
+    /// assert_eq!(board.smallest_attacker(Square::D7, White), None);
+    /// ```
+    pub fn smallest_attacker(&self, sq: Square, by: Color) -> Option<(Square, PieceType)> {
+        self.least_valuable(self.attackers_to(sq, self.occupied()) & self.color(by))
+    }
+
+    /// The number of pieces of a color that attack a square,
+    /// with the current occupancy as blockers.
+    pub fn num_attackers(&self, sq: Square, by: Color) -> u32 {
+        (self.attackers_to(sq, self.occupied()) & self.color(by)).pop_count()
+    }
+
+    /// Whether a square, usually holding a piece of a color,
+    /// is attacked by at least another piece of this color.
+    pub fn is_defended(&self, sq: Square, by: Color) -> bool {
+        self.num_attackers(sq, by) > 0
+    }
+
+    /// The pieces of a color attacked by the opponent, whether they are
+    /// defended or not. The king is left out, see `Board::checkers`.
+    pub fn threatened_pieces(&self, col: Color) -> Bitboard {
+        let occupied = self.occupied();
+        let enemy = self.color(col.opponent());
+        let mut threatened = bit::EMPTY;
+        for sq in self.color(col) & !self.piece_type(King) {
+            if self.attackers_to(sq, occupied).intersects(enemy) {
+                threatened.add(sq);
+            }
+        }
+        threatened
+    }
+
+    /// The pieces of a color attacked by the opponent, and defended by
+    /// no piece of their color. The king is left out.
+    /// 
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate chess_std;
+    /// use chess_std::prelude::*;
+    /// use chess_std::{Board, bit};
+    /// 
+    /// # fn main() {
+    /// // The knight is attacked by the pawn, the bishop is attacked but defended.
+    /// let board = Board::from_fen("1r5k/8/3p4/1B2N3/P7/8/8/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.threatened_pieces(White), merge_sq!(Square::B5, Square::E5));
+    /// assert_eq!(board.hanging_pieces(White), merge_sq!(Square::E5));
+    /// assert!(board.hanging_pieces(Black).is_empty());
+    /// # }
+    /// ```
+    pub fn hanging_pieces(&self, col: Color) -> Bitboard {
+        let mut hanging = self.threatened_pieces(col);
+        for sq in hanging {
+            if self.is_defended(sq, col) {
+                hanging.remove(sq);
+            }
+        }
+        hanging
+    }
+
+    // The least valuable piece of a set, with its type.
+    fn least_valuable(&self, pieces: Bitboard) -> Option<(Square, PieceType)> {
+        ALL_PIECE_TYPES.iter().find_map(|ptype|
+            (pieces & self.piece_type(*ptype)).first().map(|sq| (sq, *ptype))
+        )
+    }
+
+    // The material won by a move, the value of the piece that then stands
+    // on the destination, and the occupancy once the move is played.
+    fn see_initial(&self, mv: Move) -> (i32, i32, Bitboard) {
+        let value = |ptype: PieceType| ptype.value() as i32;
+        let mut occupied = self.occupied();
+        occupied.remove(mv.from);
+        occupied.remove(mv.to);
+        let mut gain = self.piece_type_at(mv.to).map_or(0, value);
+        let mut moved = value(self.type_moved_by(mv));
+        match mv.flag {
+            EnPassant(passed) => {
+                occupied.remove(passed);
+                gain = value(Pawn);
+            }
+            Promotion(new) => {
+                gain += value(new) - value(Pawn);
+                moved = value(new);
+            }
+            _ => {}
+        }
+        (gain, moved, occupied)
+    }
+
+    /// The static exchange evaluation of a move, in pawns: the material
+    /// balance after all the profitable captures on its destination.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// // The rook takes a pawn defended by a knight, and is recaptured.
+    /// let board = Board::from_fen("4k3/8/2n5/4p3/8/8/8/4RK2 w - - 0 1").unwrap();
+    /// assert_eq!(board.see(Move::quiet(Square::E1, Square::E5)), 1 - 5);
+    /// ```
+    pub fn see(&self, mv: Move) -> i32 {
+        let (first_gain, mut on_square, mut occupied) = self.see_initial(mv);
+        let mut gains = [0i32; 32];
+        gains[0] = first_gain;
+        let mut depth = 0;
+        let mut stm = self.turn.opponent();
+        while let Some((sq, ptype)) = self.least_valuable(
+                self.attackers_to(mv.to, occupied) & occupied & self.color(stm)) {
+            depth += 1;
+            gains[depth] = on_square - gains[depth - 1];
+            on_square = ptype.value() as i32;
+            occupied.remove(sq);
+            stm = stm.opponent();
+        }
+        while depth > 0 {
+            gains[depth - 1] = -std::cmp::max(-gains[depth - 1], gains[depth]);
+            depth -= 1;
+        }
+        gains[0]
+    }
+
+    /// Whether the static exchange evaluation of a move is at least
+    /// `threshold`, which is faster than `Board::see` as it stops
+    /// once the outcome is known.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::from_fen("4k3/8/2n5/4p3/8/8/8/4RK2 w - - 0 1").unwrap();
+    /// let mv = Move::quiet(Square::E1, Square::E5);
+    /// assert!(board.see_ge(mv, -4));
+    /// assert!(!board.see_ge(mv, 0));
+    /// ```
+    pub fn see_ge(&self, mv: Move, threshold: i32) -> bool {
+        let (gain, on_square, mut occupied) = self.see_initial(mv);
+        let mut swap = gain - threshold;
+        if swap < 0 {
+            return false;
+        }
+        swap = on_square - swap;
+        if swap <= 0 {
+            return true;
+        }
+        let mut stm = self.turn;
+        let mut res = true;
+        loop {
+            stm = stm.opponent();
+            let attackers = self.attackers_to(mv.to, occupied) & occupied;
+            let (sq, ptype) = match self.least_valuable(attackers & self.color(stm)) {
+                Some(attacker) => attacker,
+                None => break,
+            };
+            res = !res;
+            if ptype == King {
+                // The king may only capture when nothing defends the square.
+                return if (attackers & self.color(stm.opponent())).is_populated() {
+                    !res
+                } else {
+                    res
+                };
+            }
+            swap = ptype.value() as i32 - swap;
+            if swap < res as i32 {
+                break;
+            }
+            occupied.remove(sq);
+        }
+        res
+    }
+
 
     /// Find the king on the board, assuming the position is legal.
     pub fn king_square_of(&self, player: Color) -> Square {
@@ -408,17 +859,157 @@ impl Board {
         self.checkers.pop_count() > 0
     }
 
+    /// The number of pieces checking the current king, at most 2.
+    pub fn check_count(&self) -> u32 {
+        self.checkers.pop_count()
+    }
+
+    /// Whether the current king is checked by two pieces at once.
+    /// Only king moves are then generated, as no single move
+    /// can capture or block both checkers.
+    /// 
+    /// ```
+    /// use chess_std::Board;
+    /// 
+    /// // Discovered check from the rook, and the knight check.
+    /// let board = Board::from_fen("4k3/8/3N4/8/8/8/8/4R1K1 b - - 0 1").unwrap();
+    /// assert_eq!(board.check_count(), 2);
+    /// assert!(board.is_double_check());
+    /// assert!(board.legal_moves().all(|mv| mv.from == board.king_square()));
+    /// ```
+    pub fn is_double_check(&self) -> bool {
+        self.checkers.pop_count() == 2
+    }
+
+    /// The squares and types of the pieces checking the current king.
+    pub fn checking_pieces(&self) -> Vec<(Square, PieceType)> {
+        self.checkers
+            .filter_map(|sq| self.piece_type_at(sq).map(|ptype| (sq, ptype)))
+            .collect()
+    }
+
     /// Whether a piece is pinned to the current king.
     pub fn is_pinned(&self, sq: Square) -> bool {
         self.pinned.get(sq)
     }
 
+    /// The line along which a pinned piece may still move, `None` when
+    /// the piece at this square is not pinned.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::{Board, bit};
+    /// 
+    /// // The bishop is pinned by the rook.
+    /// let board = Board::from_fen("4k3/8/8/8/4r3/8/4B3/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.pin_ray(Square::E2), Some(bit::FILE_E));
+    /// assert_eq!(board.pin_ray(Square::E1), None);
+    /// assert_eq!(board.legal_moves_from(Square::E2).len(), 0);
+    /// ```
+    pub fn pin_ray(&self, sq: Square) -> Option<Bitboard> {
+        if self.is_pinned(sq) {
+            Some(crate::attack::fill_line(sq, self.king_square()))
+        } else {
+            None
+        }
+    }
+
+    /// The square of the slider pinning the piece at a square to the king,
+    /// `None` when the piece at this square is not pinned.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::from_fen("4k3/8/8/8/4r3/8/4B3/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.pinner_of(Square::E2), Some(Square::E4));
+    /// assert_eq!(board.pinner_of(Square::E1), None);
+    /// ```
+    pub fn pinner_of(&self, sq: Square) -> Option<Square> {
+        use crate::attack::fill_between;
+        if !self.is_pinned(sq) {
+            return None;
+        }
+        let ksq = self.king_square();
+        let mut pinners = self.pinners;
+        pinners.find(|&pinner| fill_between(ksq, pinner).get(sq))
+    }
+
+    /// The squares where a piece other than the king can capture or block
+    /// a single checker. This is empty when the king is not checked,
+    /// or when it is checked twice.
+    /// 
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate chess_std;
+    /// use chess_std::prelude::*;
+    /// use chess_std::{Board, bit};
+    /// 
+    /// # fn main() {
+    /// let board = Board::from_fen("4k3/8/8/8/7b/8/8/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.check_blocking_squares(),
+    ///            merge_sq!(Square::H4, Square::G3, Square::F2));
+    /// assert!(Board::new().check_blocking_squares().is_empty());
+    /// # }
+    /// ```
+    pub fn check_blocking_squares(&self) -> Bitboard {
+        if self.checkers.pop_count() != 1 {
+            return bit::EMPTY;
+        }
+        // A knight or a pawn can only be captured.
+        self.checkers | self.check_block_squares()
+    }
+
+    /// The squares between the king and a single checking slider,
+    /// with the square of the slider itself, where a piece can be placed
+    /// to answer the check. This is empty when the king is not checked,
+    /// checked twice, or checked by a knight or a pawn.
+    pub fn check_block_squares(&self) -> Bitboard {
+        use crate::attack::fill_between;
+        if self.checkers.pop_count() != 1 {
+            return bit::EMPTY;
+        }
+        let checker = self.checkers.scan_forward();
+        let sliders = self.opponent_piece_type(Bishop)
+                    | self.opponent_piece_type(Rook)
+                    | self.opponent_piece_type(Queen);
+        if sliders.get(checker) {
+            self.checkers | fill_between(self.king_square(), checker)
+        } else {
+            bit::EMPTY
+        }
+    }
+
+    /// The squares attacked by the piece at a square, with the current
+    /// occupancy as blockers. Unlike legal moves, this includes the defended
+    /// pieces of the same color and ignores pins.
+    /// Pawns only attack diagonally, and an empty square attacks nothing.
+    pub fn attacks_from(&self, sq: Square) -> Bitboard {
+        use crate::attack::*;
+        let occupied = self.occupied();
+        match self.piece_at(sq) {
+            None => bit::EMPTY,
+            Some(pc) => match pc.ptype {
+                Pawn   => of_pawn(pc.color, sq, bit::FULL),
+                Knight => of_knight(sq, bit::EMPTY),
+                Bishop => of_bishop(sq, bit::EMPTY, occupied),
+                Rook   => of_rook(sq, bit::EMPTY, occupied),
+                Queen  => of_queen(sq, bit::EMPTY, occupied),
+                King   => of_king(sq, bit::EMPTY),
+            }
+        }
+    }
+
     // Update pinners and checkers.
     pub(crate) fn update_attacks(&mut self) {
         use crate::attack::*;
         self.pinned = bit::EMPTY;
+        self.pinners = bit::EMPTY;
         self.checkers = bit::EMPTY;
-        let ksq = self.king_square();
+        let ksq = match self.of_color_and_type(self.turn, King).try_scan_forward() {
+            Some(sq) => sq,
+            None => return, // No king yet, e.g. from a FEN to be validated
+        };
         let bishops = self.opponent_piece_type(Bishop);
         let rooks = self.opponent_piece_type(Rook);
         let queens = self.opponent_piece_type(Queen);
@@ -428,7 +1019,10 @@ impl Board {
             let pinned = fill_between(ksq, pinner) & self.occupied();
             match pinned.pop_count() {
                 0 => self.checkers.add(pinner), // No pinned piece to stop the ray
-                1 => self.pinned |= pinned,     // A single piece is pinned
+                1 => {                          // A single piece is pinned
+                    self.pinned |= pinned;
+                    self.pinners.add(pinner);
+                },
                 _ => {}
             }
         }
@@ -462,6 +1056,9 @@ impl Board {
 
     /// Whether this position may theoretically occur.
     /// 
+    /// An en passant target must be empty, with an opponent pawn on the
+    /// square in front of it, which the pawn just passed over.
+    /// 
     /// ```
     /// use chess_std::Board;
     /// 
@@ -507,9 +1104,10 @@ impl Board {
             // Kings are touching.
             return false;
         }
-        if let Some(passed_sq) = self.ep_target {
-            if !self.opponent_piece_type(Pawn).get(passed_sq) {
-                // En passant target is not an opponent pawn.
+        if let Some(ep_sq) = self.ep_target {
+            let passed_sq = ep_sq.shift(crate::units::Direction::of_pawns(opponent));
+            if !self.opponent_piece_type(Pawn).get(passed_sq) || !self.is_empty(ep_sq) {
+                // En passant target is not behind an opponent pawn.
                 return false;
             }
         }
@@ -533,20 +1131,79 @@ impl Board {
         true
     }
 
+    /// Verifies that the piece and color bitboards agree, unlike
+    /// `Board::is_valid` which checks that the position is legal.
+    /// The internal mutators may break these invariants when misused.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        if self.color(White).intersects(self.color(Black)) {
+            return Err("A square is in both color bitboards".to_owned());
+        }
+        let mut pieces = bit::EMPTY;
+        for ptype in &ALL_PIECE_TYPES {
+            let bb = self.piece_type(*ptype);
+            if bb.intersects(pieces) {
+                return Err(format!("A {} is on a square of another piece type", ptype));
+            }
+            pieces |= bb;
+        }
+        if pieces != self.occupied() {
+            return Err("The piece bitboards differ from the color bitboards".to_owned());
+        }
+        for col in &PLAYERS {
+            if self.color(*col).pop_count() > 16 {
+                return Err(format!("Too many pieces for {}", col));
+            }
+        }
+        Ok(())
+    }
+
 
     /// A unique hash.
+    /// As in Polyglot, the en passant target is only hashed when a pawn
+    /// of the player to move attacks it, so that the positions that
+    /// only differ by an uncapturable target hash the same.
+    /// The half-move clock and the fullmove number are not hashed,
+    /// so transpositions reached at different move numbers hash the same.
     #[inline]
     pub fn zobrist_hash(&self) -> zobrist::Hash {
         self.hash
         ^ zobrist::hash_color(self.turn)
         ^ zobrist::hash_rights(self.rights)
-        ^ if let Some(sq) = self.ep_target {
-            zobrist::hash_square(sq)
-        } else {
-            zobrist::NONE_HASH
+        ^ match self.hashed_ep_target() {
+            Some(sq) => zobrist::hash_square(sq),
+            None => zobrist::NONE_HASH
         }
     }
 
+    // The en passant target, if a pawn of the player to move can take on it.
+    fn hashed_ep_target(&self) -> Option<Square> {
+        self.ep_target.filter(|&sq| crate::attack::of_pawn(self.turn.opponent(), sq, bit::FULL)
+                                        .intersects(self.own_piece_type(Pawn)))
+    }
+
+    /// Whether both boards hold the same position: the same pieces on the
+    /// same squares, player to move, castling rights and en passant target.
+    /// As in `zobrist_hash`, the target only counts when a pawn can take on
+    /// it, and the move clocks are ignored. Unlike the equality, which
+    /// trusts the hashes, the pieces are compared square by square.
+    ///
+    /// ```
+    /// use chess_std::Board;
+    ///
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 12 40").unwrap();
+    /// let other = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+    /// assert!(board.same_position(&other));
+    /// let other = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 12 40").unwrap();
+    /// assert!(!board.same_position(&other));
+    /// ```
+    pub fn same_position(&self, other: &Board) -> bool {
+        self.pieces == other.pieces &&
+        self.colors == other.colors &&
+        self.turn == other.turn &&
+        self.rights == other.rights &&
+        self.hashed_ep_target() == other.hashed_ep_target()
+    }
+
     pub(crate) fn rehash(&mut self) -> &Self {
         self.hash = zobrist::INITIAL_HASH;
         for pc in &ALL_PIECES {
@@ -569,11 +1226,28 @@ impl Board {
 
     /// Return a 'pretty' Unicode board representation.
     pub fn to_unicode(&self) -> String {
-        let mut s = "  a b c d e f g h".to_string();
-        for r in (Rank::R1..=Rank::R8).rev() {
+        self.to_unicode_oriented(White)
+    }
+
+    /// Return a 'pretty' Unicode board representation, from the point of view
+    /// of a player: Black sees the board rotated, with the labels following.
+    pub fn to_unicode_oriented(&self, from: Color) -> String {
+        let mut ranks: Vec<_> = (Rank::R1..=Rank::R8).collect();
+        let mut files: Vec<_> = (File::A..=File::H).collect();
+        if from == White {
+            ranks.reverse();
+        } else {
+            files.reverse();
+        }
+        let mut s = " ".to_string();
+        for f in &files {
+            s.push(' ');
+            s.push(f.to_char());
+        }
+        for &r in &ranks {
             s.push('\n');
             s.push(r.to_char());
-            for f in File::A..=File::H {
+            for &f in &files {
                 let at = self.piece_at(Square::new(r, f));
                 s.push(if let Some(pc) = at { pc.symbol() } else { '-' });
             }
@@ -591,6 +1265,126 @@ impl Board {
         }
         arr
     }
+
+    /// The length of `Board::encode`.
+    pub const ENCODED_LEN: usize = 42;
+
+    /// Serialize the whole board state to `Board::ENCODED_LEN` bytes.
+    /// 
+    /// The layout is:
+    /// - bytes 0 to 31: the grid, two squares per byte from A1 to H8. The low
+    ///   nibble holds the even square, and a nibble is 0 for an empty square
+    ///   or 1 + the piece index (white pawn to black king) otherwise.
+    /// - byte 32: bit 0 is set when Black plays, bits 1 to 4 are the castling
+    ///   rights, White king side, White queen side, Black king side and
+    ///   Black queen side.
+    /// - byte 33: the en passant target square index, or 255 if there is none.
+    /// - bytes 34 to 37: the half-move clock, in little endian.
+    /// - bytes 38 to 41: the fullmove number, in little endian.
+    /// 
+    /// ```
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::new();
+    /// let bytes = board.encode();
+    /// assert_eq!(bytes.len(), Board::ENCODED_LEN);
+    /// assert_eq!(Board::decode(&bytes), Ok(board));
+    /// ```
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; Self::ENCODED_LEN];
+        for pc in &ALL_PIECES {
+            for sq in self.piece(*pc) {
+                let nibble = pc.index() as u8 + 1;
+                bytes[sq.index() / 2] |= nibble << (4 * (sq.index() % 2));
+            }
+        }
+        let mut meta = self.turn.index() as u8;
+        for (i, (col, side)) in RIGHTS_ORDER.iter().enumerate() {
+            if self.has_right(*col, *side) {
+                meta |= 1 << (i + 1);
+            }
+        }
+        bytes[32] = meta;
+        bytes[33] = self.ep_target.map_or(255, |sq| sq.index() as u8);
+        bytes[34..38].copy_from_slice(&self.half_move_clock.to_le_bytes());
+        bytes[38..42].copy_from_slice(&self.fullmove_number.to_le_bytes());
+        bytes
+    }
+
+    /// Deserialize a board encoded by `Board::encode`.
+    /// 
+    /// Returns an error if the data is malformed or the position is invalid.
+    pub fn decode(bytes: &[u8]) -> Result<Board, String> {
+        use std::convert::TryInto;
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(format!("Expected {} bytes, got {}", Self::ENCODED_LEN, bytes.len()));
+        }
+        let mut board = Board::default();
+        for i in 0..Square::NUM {
+            let nibble = (bytes[i / 2] >> (4 * (i % 2))) & 0xf;
+            match nibble as usize {
+                0 => {},
+                n if n <= NUM_PIECES => {
+                    board.add_piece(ALL_PIECES[n - 1], Square(i as u8));
+                }
+                n => return Err(format!("Invalid piece code: {}", n)),
+            }
+        }
+        let meta = bytes[32];
+        if meta >> 5 != 0 {
+            return Err(format!("Invalid metadata: {}", meta));
+        }
+        board.turn = if meta & 1 == 0 { White } else { Black };
+        board.rights = NO_PLAYERS_RIGHTS;
+        for (i, (col, side)) in RIGHTS_ORDER.iter().enumerate() {
+            if meta & (1 << (i + 1)) != 0 {
+                board.add_right(*col, *side);
+            }
+        }
+        board.ep_target = match bytes[33] {
+            255 => None,
+            i if (i as usize) < Square::NUM => Some(Square(i)),
+            i => return Err(format!("Invalid en passant square: {}", i)),
+        };
+        board.half_move_clock = u32::from_le_bytes(bytes[34..38].try_into().unwrap());
+        board.fullmove_number = u32::from_le_bytes(bytes[38..42].try_into().unwrap());
+        if board.fullmove_number == 0 {
+            return Err("The fullmove number starts at 1".to_owned());
+        }
+        if !board.is_valid() {
+            return Err("Invalid position".to_owned());
+        }
+        board.update_attacks();
+        Ok(board)
+    }
+}
+
+// The order of the castling rights in `Board::encode`.
+const RIGHTS_ORDER: [(Color, Side); 4] = [
+    (White, Side::King), (White, Side::Queen), (Black, Side::King), (Black, Side::Queen)
+];
+
+impl Board {
+    /// An equality check that compares the pieces and all the metadata,
+    /// including the clocks, instead of the zobrist hash.
+    /// 
+    /// ```
+    /// use chess_std::Board;
+    /// 
+    /// let mut board = Board::new();
+    /// assert!(board.exact_eq(&Board::new()));
+    /// board.half_move_clock = 3;
+    /// assert!(board == Board::new() && !board.exact_eq(&Board::new()));
+    /// ```
+    pub fn exact_eq(&self, other: &Board) -> bool {
+        self.pieces == other.pieces &&
+        self.colors == other.colors &&
+        self.turn == other.turn &&
+        self.half_move_clock == other.half_move_clock &&
+        self.ep_target == other.ep_target &&
+        self.rights == other.rights &&
+        self.fullmove_number == other.fullmove_number
+    }
 }
 
 /// A fast equality check, using zobrist hashes.
@@ -639,8 +1433,586 @@ mod board_test {
                 board.remove_piece(*pc, sq);
             }
         }
-        assert_eq!(board, Board::default());
+        assert!(board.exact_eq(&Board::default()));
         board = Board::new();
-        assert_eq!(Board::from_fen(&board.to_fen()).unwrap(), board);
+        assert!(Board::from_fen(&board.to_fen()).unwrap().exact_eq(&board));
+    }
+
+    #[test]
+    fn test_pin_ray() {
+        // Pinned on each kind of line, including diagonals off the main ones.
+        let board = Board::from_fen("4k3/8/8/1B6/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(board.pin_ray(Square::D7), None);
+        let board = Board::from_fen("4k3/3n4/8/1B6/8/8/8/4K3 b - - 0 1").unwrap();
+        let ray = board.pin_ray(Square::D7).unwrap();
+        assert!(ray.get(Square::A4) && ray.get(Square::B5) && ray.get(Square::E8));
+        let board = Board::from_fen("rnbqkb1r/pp2pppp/2p2n2/1B1P4/8/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 4").unwrap();
+        assert!(board.pin_ray(Square::C6).unwrap().get(Square::B5));
+        assert!(board.is_move_legal(Move::quiet(Square::C6, Square::B5)));
+        assert!(!board.is_move_legal(Move::quiet(Square::C6, Square::D5)));
+    }
+
+    #[test]
+    fn test_pinners() {
+        // The Ruy Lopez pin, and a rook pinning a bishop on the g-file.
+        let board = Board::from_fen("r1bqkbnr/ppp2ppp/2np4/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 1 4").unwrap();
+        assert_eq!(board.pinned(), bit::single(Square::C6));
+        assert_eq!(board.pinners(), bit::single(Square::B5));
+        assert_eq!(board.pinner_of(Square::C6), Some(Square::B5));
+        assert_eq!(board.pinner_of(Square::D6), None);
+        let board = Board::from_fen("6k1/6b1/8/8/8/8/8/K5R1 b - - 0 1").unwrap();
+        assert_eq!(board.pinner_of(Square::G7), Some(Square::G1));
+        // Two pins at once.
+        let board = Board::from_fen("4k3/3nn3/8/1B6/8/8/8/4R1K1 b - - 0 1").unwrap();
+        assert_eq!(board.pinners(), merge_sq!(Square::B5, Square::E1));
+        assert_eq!(board.pinner_of(Square::D7), Some(Square::B5));
+        assert_eq!(board.pinner_of(Square::E7), Some(Square::E1));
+        let mut board = board;
+        let undo = board.make_null_move().unwrap();
+        assert!(board.pinners().is_empty());
+        board.unmake_null_move(undo);
+        assert_eq!(board.pinners(), merge_sq!(Square::B5, Square::E1));
+    }
+
+    #[test]
+    fn test_from_ascii() -> Result<(), String> {
+        let start = Board::new();
+        let board = Board::from_ascii(&start.to_string())?;
+        assert!(board.pieces == start.pieces && board.colors == start.colors);
+        assert_eq!(board.hash, start.hash);
+        assert_eq!(board.rights, NO_PLAYERS_RIGHTS);
+        let board = Board::from_ascii("
+            8 - - - - k - - -
+            7 - - - - - - - -
+            6 - - - - - - - -
+            5 - - - - - - - -
+            4 - - - - r - - -
+            3 - - - - - - - -
+            2 - - - - B - - -
+            1 - - - - K - - -")?;
+        assert!(board.exact_eq(&Board::from_fen("4k3/8/8/8/4r3/8/4B3/4K3 w - - 0 1")?));
+        assert!(board.is_pinned(Square::E2));
+        assert!(Board::from_ascii(&start.to_string().replace("1 R", "2 R")).is_err());
+        assert!(Board::from_ascii(&start.to_string().replace("- -", "x -")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unicode_oriented() -> Result<(), String> {
+        let board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")?;
+        assert_eq!(board.to_unicode(), board.to_unicode_oriented(White));
+        let grid = |s: String| -> Vec<String> {
+            s.lines().skip(1).map(|line| line.chars().skip(1).collect()).collect()
+        };
+        let white = grid(board.to_unicode_oriented(White));
+        let black = grid(board.to_unicode_oriented(Black));
+        let rotated: Vec<String> = white.iter().rev()
+            .map(|line| line.chars().rev().collect())
+            .collect();
+        assert_eq!(black, rotated);
+        let black = board.to_unicode_oriented(Black);
+        assert!(black.starts_with("  h g f e d c b a\n1"));
+        assert!(black.lines().last().unwrap().starts_with('8'));
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_counters() -> Result<(), String> {
+        let mut board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")?;
+        assert_eq!((board.halfmove_clock(), board.fullmove_number()), (2, 3));
+        board.apply_move(Move::quiet(Square::F1, Square::B5));
+        assert_eq!((board.halfmove_clock(), board.fullmove_number()), (3, 3));
+        board.apply_move(Move::quiet(Square::G8, Square::F6));
+        assert_eq!((board.halfmove_clock(), board.fullmove_number()), (4, 4));
+        // The capture resets the clock, but not the move number.
+        board.apply_move(Move::quiet(Square::B5, Square::C6));
+        assert_eq!((board.halfmove_clock(), board.fullmove_number()), (0, 4));
+        assert_eq!(board.num_moves_played(), 7);
+        board.apply_move(Move::quiet(Square::D7, Square::C6));
+        assert_eq!((board.halfmove_clock(), board.fullmove_number()), (0, 5));
+        // The move numbers start at 1.
+        assert!(Board::from_fen("8/8/8/8/8/8/8/K6k w - - 0 0").is_err());
+        assert!(Board::from_fen_relaxed("8/8/8/8/8/8/8/K6k w - - 0 0").is_err());
+        let mut bytes = Board::new().encode();
+        bytes[38..42].copy_from_slice(&0u32.to_le_bytes());
+        assert!(Board::decode(&bytes).is_err());
+        let mut board = Board::new();
+        board.fullmove_number = 0;
+        assert_eq!(board.num_moves_played(), 0);
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 99 80")?;
+        assert!(!board.can_claim_draw_with(crate::DrawType::FiftyMoveRule));
+        let board = board.play_move(Move::quiet(Square::A1, Square::A2));
+        assert_eq!(board.halfmove_clock(), 100);
+        assert!(board.can_claim_draw_with(crate::DrawType::FiftyMoveRule));
+        Ok(())
+    }
+
+    #[test]
+    fn test_checking_pieces() -> Result<(), String> {
+        let board = Board::from_fen("4k3/8/8/4N3/8/8/8/4R1K1 w - - 0 1")?;
+        assert!(board.checking_pieces().is_empty());
+        // The knight checks and uncovers the rook.
+        let board = board.play_move(Move::quiet(Square::E5, Square::D6));
+        assert!(board.is_double_check());
+        assert_eq!(board.checking_pieces(),
+                   vec![(Square::E1, Rook), (Square::D6, Knight)]);
+        let board = Board::from_fen("4k3/8/8/8/1b6/8/8/4K3 w - - 0 1")?;
+        assert_eq!(board.checking_pieces(), vec![(Square::B4, Bishop)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_block_squares() -> Result<(), String> {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b - - 0 1")?;
+        assert!(board.check_block_squares().is_empty());
+        let board = board.play_move(Move::quiet(Square::E8, Square::D7))
+                         .play_move(Move::quiet(Square::A1, Square::A7));
+        assert_eq!(board.check_block_squares(),
+                   bit::RANK_7 & (bit::FILE_A | bit::FILE_B | bit::FILE_C));
+        // The rook checks on the file from a distance.
+        let board = Board::from_fen("3k4/8/8/8/8/8/8/3RK3 b - - 0 1")?;
+        let expected = bit::FILE_D & !(bit::RANK_8 | bit::RANK_1) | bit::single(Square::D1);
+        assert_eq!(board.check_block_squares(), expected);
+        assert_eq!(board.check_blocking_squares(), expected);
+        // A knight check cannot be blocked.
+        let board = Board::from_fen("4k3/8/3N4/8/8/8/8/4K3 b - - 0 1")?;
+        assert!(board.check_block_squares().is_empty());
+        assert_eq!(board.check_blocking_squares(), bit::single(Square::D6));
+        // Neither can a double check.
+        let board = Board::from_fen("4k3/8/3N4/8/8/8/8/4R1K1 b - - 0 1")?;
+        assert!(board.check_block_squares().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_en_passant_matching() -> Result<(), String> {
+        use crate::MoveGenerator;
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1")?;
+        let generated = Move::en_passant(Square::E5, Square::D6, Square::D5);
+        // Only the origin and the destination are known.
+        let from_gui = Move::en_passant(Square::E5, Square::D6, Square::D6);
+        assert!(board.is_move_legal(generated));
+        assert!(board.is_move_legal(from_gui));
+        assert!(board.legal_moves_from(Square::E5).contains(from_gui));
+        assert!(board.is_pseudo_legal(from_gui));
+        assert!(Move::validated(Square::E5, Square::D6, EnPassant(Square::D6), &board).is_ok());
+        let wrong = Move::en_passant(Square::E5, Square::F6, Square::F5);
+        assert!(!board.is_move_legal(wrong));
+        assert!(!board.legal_moves_from(Square::E5).contains(wrong));
+        assert!(!board.is_pseudo_legal(wrong));
+        assert!(Move::validated(Square::E5, Square::F6, EnPassant(Square::F5), &board).is_err());
+        assert_eq!(board.play_move(from_gui), board.play_move(generated));
+        assert_eq!(board.play_move(from_gui).piece_at(Square::D5), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_uci_position() -> Result<(), String> {
+        let (board, moves) = Board::from_uci_position("startpos moves e2e4 c7c5 g1f3")?;
+        assert_eq!(board, Board::new());
+        assert_eq!(moves.len(), 3);
+        assert_eq!(moves[2], Move::quiet(Square::G1, Square::F3));
+        assert_eq!(Board::from_uci_position("startpos")?.1, vec![]);
+
+        let fen = "r3k2r/8/8/8/8/8/1p6/R3K2R b KQkq - 0 1";
+        let (board, moves) = Board::from_uci_position(&format!("fen {} moves e8c8 e1g1 b2a1q", fen))?;
+        assert_eq!(board, Board::from_fen(fen)?);
+        assert_eq!(moves, vec![
+            Move::castling(Black, Side::Queen),
+            Move::castling(White, Side::King),
+            Move::promotion(Square::B2, Square::A1, Queen),
+        ]);
+        // Without the move counters.
+        let (board, _) = Board::from_uci_position("fen 4k3/8/8/8/8/8/8/4K3 w - -")?;
+        assert_eq!(board.king_square(), Square::E1);
+
+        assert!(Board::from_uci_position("").is_err());
+        assert!(Board::from_uci_position("startpos e2e4").is_err());
+        assert!(Board::from_uci_position("startpos moves e2e5").is_err());
+        assert!(Board::from_uci_position("fen moves e2e4").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pawn_chain_defenders() -> Result<(), String> {
+        let board = Board::from_fen("4k3/8/8/4P3/3P4/2P2N2/1P6/4K3 w - - 0 1")?;
+        assert_eq!(board.num_attackers(Square::E5, White), 2);
+        assert_eq!(board.num_attackers(Square::D4, White), 2);
+        assert_eq!(board.num_attackers(Square::C3, White), 1);
+        assert_eq!(board.num_attackers(Square::B2, White), 0);
+        assert!(board.is_defended(Square::C3, White));
+        assert!(!board.is_defended(Square::B2, White));
+        assert_eq!(board.num_attackers(Square::D6, White), 1);
+        assert_eq!(board.num_attackers(Square::E5, Black), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_halfmove_clock_fen() -> Result<(), String> {
+        let fen = "4k3/4p3/8/8/8/8/8/4K1N1 w - - 42 30";
+        let board = Board::from_fen(fen)?;
+        assert_eq!(board.halfmove_clock(), 42);
+        assert_eq!(board.to_fen(), fen);
+        let board = board.play_move(Move::quiet(Square::G1, Square::F3));
+        assert_eq!(board.halfmove_clock(), 43);
+        assert!(board.to_fen().ends_with(" 43 30"));
+        // A pawn move resets the clock.
+        let board = board.play_move(Move::quiet(Square::E7, Square::E6));
+        assert_eq!(board.halfmove_clock(), 0);
+        assert!(board.to_fen().ends_with(" 0 31"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pseudo_legal() -> Result<(), String> {
+        let board = Board::new();
+        assert!(board.is_pseudo_legal(Move::quiet(Square::E2, Square::E4)));
+        assert!(!board.is_pseudo_legal(Move::NONE));
+        assert!(!board.is_pseudo_legal(Move::quiet(Square::E2, Square::E5)));
+        assert!(!board.is_pseudo_legal(Move::quiet(Square::E7, Square::E5))); // Not the turn
+        assert!(!board.is_pseudo_legal(Move::quiet(Square::E4, Square::E5))); // Empty square
+        assert!(!board.is_pseudo_legal(Move::quiet(Square::B1, Square::D2))); // Own piece
+        assert!(!board.is_pseudo_legal(Move::quiet(Square::A1, Square::A3))); // Blocked
+        assert!(!board.is_pseudo_legal(Move::quiet(Square::E2, Square::D3))); // Nothing to capture
+        assert!(!board.is_pseudo_legal(Move::castling(White, Side::King)));
+
+        let board = Board::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 2")?;
+        assert!(board.is_pseudo_legal(Move::castling(White, Side::King)));
+        assert!(board.is_pseudo_legal(Move::en_passant(Square::E5, Square::D6, Square::D5)));
+        assert!(!board.is_pseudo_legal(Move::en_passant(Square::E5, Square::F6, Square::F5)));
+        assert!(board.is_pseudo_legal(Move::promotion(Square::B7, Square::A8, Queen)));
+        assert!(!board.is_pseudo_legal(Move::quiet(Square::B7, Square::B8)));
+        // The king moves next to the enemy king, which only the legality test rejects.
+        let board = Board::from_fen("8/8/8/8/8/2k5/8/K7 w - - 0 1")?;
+        assert!(board.is_pseudo_legal(Move::quiet(Square::A1, Square::B2)));
+        assert!(!board.is_move_legal(Move::quiet(Square::A1, Square::B2)));
+
+        for fen in &[
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ] {
+            let board = Board::from_fen(fen)?;
+            assert!(board.legal_moves().all(|mv| board.is_pseudo_legal(mv)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_phase() -> Result<(), String> {
+        let board = Board::new();
+        assert_eq!(board.non_pawn_material(Black), 31);
+        assert_eq!(board.game_phase(), Board::MAX_PHASE);
+        let board = Board::from_fen("4k3/pppppppp/8/8/8/8/PPPPPPPP/4K3 w - - 0 1")?;
+        assert_eq!(board.non_pawn_material(White), 0);
+        assert_eq!(board.game_phase(), 0);
+        assert_eq!(Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1")?.game_phase(), 0);
+        // Rooks and queens alone.
+        let board = Board::from_fen("r2qk2r/8/8/8/8/8/8/R2QK2R w - - 0 1")?;
+        assert_eq!(board.non_pawn_material(White), 19);
+        assert_eq!(board.game_phase(), 14);
+        // Extra queens do not go beyond the starting phase.
+        let board = Board::from_fen("rnbqkbnr/QQQQQQQQ/8/8/8/8/qqqqqqqq/RNBQKBNR w - - 0 1")?;
+        assert_eq!(board.game_phase(), Board::MAX_PHASE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fen_fields() -> Result<(), String> {
+        let fen = "r3k2r/8/8/3pP3/8/8/8/R3K2R w Kq d6 0 2";
+        let mut board = Board::from_fen(fen)?;
+        assert_eq!(board.placement_fen(), "r3k2r/8/8/3pP3/8/8/8/R3K2R");
+        assert_eq!(board.castling_fen(), "Kq");
+        assert_eq!(board.ep_fen(), "d6");
+        assert_eq!(board.to_fen(), fen);
+        // Moving the rook loses the last white right.
+        board.apply_move(Move::quiet(Square::H1, Square::H2));
+        assert_eq!((board.castling_fen(), board.ep_fen()), ("q".to_owned(), "-".to_owned()));
+        board.apply_move(Move::quiet(Square::A8, Square::A7));
+        assert_eq!(board.castling_fen(), "-");
+        assert!(Board::from_fen(&board.to_fen())?.exact_eq(&board));
+        Ok(())
+    }
+
+    #[test]
+    fn test_attacks_from() {
+        let board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3").unwrap();
+        assert_eq!(board.attacks_from(Square::E4), merge_sq!(Square::D5, Square::F5));
+        assert_eq!(board.attacks_from(Square::F3),
+                   merge_sq!(Square::E1, Square::G1, Square::D2, Square::H2,
+                             Square::D4, Square::H4, Square::E5, Square::G5));
+        assert_eq!(board.attacks_from(Square::F1), merge_sq!(Square::E2, Square::D3, Square::C4, Square::B5, Square::A6, Square::G2));
+        assert_eq!(board.attacks_from(Square::H1), merge_sq!(Square::G1, Square::F1, Square::H2));
+        assert_eq!(board.attacks_from(Square::D1), merge_sq!(Square::C1, Square::E1, Square::C2, Square::D2, Square::E2, Square::F3));
+        assert_eq!(board.attacks_from(Square::E1), merge_sq!(Square::D1, Square::F1, Square::D2, Square::E2, Square::F2));
+        // The opponent pieces too.
+        assert_eq!(board.attacks_from(Square::C6),
+                   merge_sq!(Square::A5, Square::A7, Square::B4, Square::B8,
+                             Square::D4, Square::D8, Square::E5, Square::E7));
+        assert_eq!(board.attacks_from(Square::E3), bit::EMPTY);
+    }
+
+    // Call `visit` on the boards of `num_games` random games of at most
+    // `max_plies`, with a fixed linear congruential generator.
+    fn random_games(mut seed: u64, num_games: usize, max_plies: usize, mut visit: impl FnMut(&Board)) {
+        for _ in 0..num_games {
+            let mut board = Board::new();
+            for _ in 0..max_plies {
+                visit(&board);
+                let moves: Moves = board.legal_moves().into();
+                if moves.is_empty() {
+                    break;
+                }
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                board.apply_move(moves[(seed >> 33) as usize % moves.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        random_games(0x2545_f491, 20, 60, |board| {
+            let bytes = board.encode();
+            let decoded = Board::decode(&bytes).unwrap();
+            assert!(decoded.exact_eq(board));
+            assert_eq!(decoded.zobrist_hash(), board.zobrist_hash());
+            assert_eq!(decoded.encode(), bytes);
+        });
+        assert!(Board::decode(&[0; 3]).is_err());
+        assert!(Board::decode(&[0; Board::ENCODED_LEN]).is_err()); // No kings
+    }
+
+    #[test]
+    fn test_material_insufficient() {
+        let insufficient = |fen| Board::from_fen(fen).unwrap().is_material_insufficient();
+        assert!(insufficient("4k3/8/8/8/8/8/8/4K3 w - - 0 1"));
+        assert!(insufficient("4k3/8/8/8/8/8/8/4KN2 w - - 0 1"));
+        // KB vs kb, same-colored bishops
+        assert!(insufficient("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+        // KB vs kb, opposite-colored bishops
+        assert!(!insufficient("2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1"));
+        // KBB vs k, both bishops on dark squares
+        assert!(insufficient("4k3/8/8/8/8/8/8/B1B1K3 w - - 0 1"));
+        assert!(!insufficient("4k3/8/8/8/8/8/8/BB2K3 w - - 0 1"));
+        assert!(!insufficient("4k3/8/8/8/8/8/P7/B3K3 w - - 0 1"));
+    }
+
+    #[test]
+    fn test_see_ge() {
+        // Captures from random games.
+        let mut num_captures = 0;
+        random_games(0x9e37_79b9, 20, 80, |board| {
+            for mv in board.legal_captures() {
+                num_captures += 1;
+                let see = board.see(mv);
+                for threshold in -10..=10 {
+                    assert_eq!(board.see_ge(mv, threshold), see >= threshold,
+                               "{} with SEE {} at {}", mv, see, board.to_fen());
+                }
+            }
+        });
+        assert!(num_captures > 100);
+    }
+
+    #[test]
+    fn test_place_clear() {
+        let mut board = Board::new();
+        let hash = board.zobrist_hash();
+        board.place(B_QUEEN, Square::E3).unwrap();
+        assert_eq!(board.piece_at(Square::E3), Some(B_QUEEN));
+        assert_ne!(board.zobrist_hash(), hash);
+        assert!(board.place(W_KNIGHT, Square::E3).is_err());
+        board.clear(Square::E3);
+        assert_eq!(board.zobrist_hash(), hash);
+        assert!(board.exact_eq(&Board::new()));
+
+        // The attacks are updated.
+        board.clear(Square::E2);
+        board.place(B_ROOK, Square::E4).unwrap();
+        assert!(board.in_check());
+    }
+
+    #[test]
+    fn test_with_turn() -> Result<(), String> {
+        // White is checked by the bishop, and the black knight is pinned by the rook.
+        let board = Board::from_fen("4k3/4n3/8/8/1b6/8/4R3/4K3 w - - 0 1")?;
+        assert_eq!(board.checkers(), bit::single(Square::B4));
+        assert!(board.pinned().is_empty());
+        let flipped = board.with_turn(board.turn.opponent());
+        assert_eq!(flipped.turn, Black);
+        assert!(flipped.checkers().is_empty());
+        assert_eq!(flipped.pinned(), bit::single(Square::E7));
+        assert_eq!(flipped.pinners(), bit::single(Square::E2));
+        assert_ne!(flipped.zobrist_hash(), board.zobrist_hash());
+        // Back to the original side to move.
+        assert!(flipped.with_turn(White).exact_eq(&board));
+        assert!(board.with_turn(White).exact_eq(&board));
+
+        // The en passant target belongs to the previous turn.
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 2")?;
+        assert_eq!(board.with_turn(Black).ep_target, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_winning_captures() -> Result<(), String> {
+        // The queen would take a pawn defended by a pawn, the rook is free.
+        let board = Board::from_fen("7k/r7/4p3/3p4/8/8/8/R2Q2K1 w - - 0 1")?;
+        let queen_capture = Move::quiet(Square::D1, Square::D5);
+        let rook_capture = Move::quiet(Square::A1, Square::A7);
+        assert!(board.legal_captures().any(|mv| mv == queen_capture));
+        assert_eq!(board.winning_captures(), vec![rook_capture]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_xray_attackers_to() -> Result<(), String> {
+        // The bishop stands between the rook and the black queen.
+        let board = Board::from_fen("3q3k/8/8/8/3B4/8/8/3R2K1 w - - 0 1")?;
+        let bishop = bit::single(Square::D4);
+        assert!(board.xray_attackers_to(Square::D8, White, bit::EMPTY).is_empty());
+        assert_eq!(board.xray_attackers_to(Square::D8, White, bishop),
+                   bit::single(Square::D1));
+        assert_eq!(board.xray_attackers_to(Square::D8, Black, bishop), bit::EMPTY);
+        for sq in bit::FULL {
+            assert_eq!(board.xray_attackers_to(sq, Black, bit::EMPTY),
+                       board.attackers_to(sq, board.occupied()) & board.color(Black));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_en_passant_hash() -> Result<(), String> {
+        // No black pawn can take on e3.
+        let board = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1")?;
+        let no_target = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1")?;
+        assert_eq!(board.zobrist_hash(), no_target.zobrist_hash());
+        let board = Board::from_fen("4k3/8/8/8/p3P3/8/8/4K3 b - e3 0 1")?;
+        let no_target = Board::from_fen("4k3/8/8/8/p3P3/8/8/4K3 b - - 0 1")?;
+        assert_eq!(board.zobrist_hash(), no_target.zobrist_hash());
+
+        let board = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1")?;
+        let no_target = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1")?;
+        assert_ne!(board.zobrist_hash(), no_target.zobrist_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_position() -> Result<(), String> {
+        // The same position, reached later in the game.
+        let board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")?;
+        let other = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 6 5")?;
+        assert!(board.same_position(&other));
+        assert_eq!(board.zobrist_hash(), other.zobrist_hash());
+        assert_ne!(board.to_fen(), other.to_fen());
+
+        let black = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 2 3")?;
+        assert!(!board.same_position(&black));
+        let rights = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w Kkq - 2 3")?;
+        assert!(!board.same_position(&rights));
+        // The target is ignored when no pawn can take on it.
+        let board = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1")?;
+        let no_target = Board::from_fen("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1")?;
+        assert!(board.same_position(&no_target));
+        let board = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1")?;
+        let no_target = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1")?;
+        assert!(!board.same_position(&no_target));
+        // 1. e4 Nf6 2. Nf3 and 1. Nf3 Nf6 2. e4 transpose.
+        let board = Board::from_fen("rnbqkb1r/pppppppp/5n2/8/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2")?;
+        let other = Board::from_fen("rnbqkb1r/pppppppp/5n2/8/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq e3 0 2")?;
+        assert!(board.same_position(&other));
+        assert_eq!(board.zobrist_hash(), other.zobrist_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_apply_move_promotions() -> Result<(), String> {
+        let board = Board::from_fen("4k3/P7/8/8/8/8/8/4K1N1 w - - 0 1")?;
+        // A knight cannot promote, and a pawn reaching the last rank must.
+        for mv in [Move::promotion(Square::G1, Square::F3, Queen), Move::quiet(Square::A7, Square::A8)] {
+            let mut next = board.clone();
+            assert!(!board.is_move_legal(mv));
+            assert!(next.try_apply_move(mv).is_err());
+            assert_eq!(next.to_fen(), board.to_fen());
+        }
+        let mut next = board.clone();
+        next.try_apply_move(Move::promotion(Square::A7, Square::A8, Queen))?;
+        assert_eq!(next.piece_at(Square::A8), Some(Piece { color: White, ptype: Queen }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_invariants() -> Result<(), String> {
+        Board::new().check_invariants()?;
+        // A knight without a color.
+        let mut board = Board::new();
+        board.pieces[Knight.index()].add(Square::E4);
+        assert!(board.check_invariants().is_err());
+        // A square in both color boards.
+        let mut board = Board::new();
+        board.colors[Black.index()].add(Square::E2);
+        assert!(board.check_invariants().is_err());
+        // A square in two piece boards.
+        let mut board = Board::new();
+        board.pieces[Queen.index()].add(Square::E2);
+        assert!(board.check_invariants().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_status() -> Result<(), String> {
+        use crate::game::{GameStatus, DrawType};
+        let status = |fen| Board::from_fen(fen).map(|board| board.status());
+        assert_eq!(status("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")?,
+                   GameStatus::Ongoing);
+        // Back rank mate.
+        assert_eq!(status("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1")?,
+                   GameStatus::Checkmate(White));
+        assert_eq!(status("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1")?, GameStatus::Stalemate);
+        assert_eq!(status("4k3/8/8/8/8/8/8/3NK3 w - - 0 1")?,
+                   GameStatus::DrawClaimable(DrawType::InsufficientMaterial));
+        assert_eq!(status("4k3/8/8/8/8/8/8/3QK3 w - - 100 80")?,
+                   GameStatus::DrawClaimable(DrawType::FiftyMoveRule));
+        // Mate on the last move of the fifty is still a mate.
+        assert_eq!(status("R5k1/5ppp/8/8/8/8/8/6K1 b - - 100 80")?,
+                   GameStatus::Checkmate(White));
+        Ok(())
+    }
+
+    #[test]
+    fn test_material_signature() -> Result<(), String> {
+        let kqk = Board::from_fen("8/8/3k4/8/8/2Q5/8/4K3 w - - 0 1")?;
+        let kkq = Board::from_fen("4k3/8/2q5/8/8/3K4/8/8 b - - 0 1")?;
+        assert_eq!(kqk.material_signature(), "KQvK");
+        assert_eq!(kkq.material_signature(), "KvKQ");
+        // Swapping the colors swaps the sides of the signature.
+        let (white, black) = kqk.material_signature().split_once('v').map(
+            |(w, b)| (w.to_owned(), b.to_owned())).unwrap();
+        assert_eq!(kkq.material_signature(), format!("{}v{}", black, white));
+        Ok(())
+    }
+
+    #[test]
+    fn test_valid_en_passant() -> Result<(), String> {
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1")?;
+        assert!(board.is_valid());
+        // No pawn passed over the target.
+        let mut moved = board.clone();
+        moved.ep_target = Some(Square::E6);
+        assert!(!moved.is_valid());
+        // The target is occupied.
+        let mut occupied = board.clone();
+        occupied.place(B_KNIGHT, Square::D6)?;
+        assert!(!occupied.is_valid());
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_king() {
+        // Attacks are not computed from an off-board king square.
+        let board = Board::from_fen("8/8/8/8/8/8/8/r6K b - - 0 1").unwrap();
+        assert!(board.checkers().is_empty() && board.pinned().is_empty());
+        assert!(!board.is_valid());
     }
 }
\ No newline at end of file
@@ -4,10 +4,20 @@
 use crate::position::*;
 use crate::prelude::*;
 use crate::units::Direction;
-use crate::bit;
+use crate::bit::{self, Bitboard};
 use crate::moves::{PGNMove, CheckType, castling};
 use crate::movegen::{MoveGen, MoveGenMasked, MoveGenerator};
-use crate::game::{GameResult, WinType, DrawType};
+use crate::game::{GameResult, GameStatus, WinType, DrawType};
+
+
+/// The state needed to take back a null move, see `Board::make_null_move`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NullUndo {
+    ep_target: Option<Square>,
+    checkers: Bitboard,
+    pinned: Bitboard,
+    pinners: Bitboard,
+}
 
 
 impl Board {
@@ -65,8 +75,122 @@ impl Board {
         } else {
             Some(Square::from_san(sq_data)?)
         };
-        board.half_move_clock = items[4].parse().unwrap_or(1);
-        board.last_cap_or_push = board.half_move_clock*2;
+        board.half_move_clock = items[4].parse()
+            .map_err(|_| "Couldn't parse halfmove clock".to_owned())?;
+        board.fullmove_number = items[5].parse()
+            .map_err(|_| "Couldn't parse fullmove number".to_owned())?;
+        if board.fullmove_number == 0 {
+            return Err("The fullmove number starts at 1".to_owned());
+        }
+        Ok(board)
+    }
+
+    /// Builds a Board from a FEN that may omit the clocks, with 4 to 6 fields.
+    /// The halfmove clock defaults to 0 and the fullmove number to 1.
+    /// ```
+    /// use chess_std::{Board};
+    /// let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3";
+    /// let board = Board::from_fen_relaxed(fen).unwrap();
+    /// let full = Board::from_fen(&format!("{} 0 1", fen)).unwrap();
+    /// assert_eq!(board, full);
+    /// assert_eq!(board.half_move_clock, full.half_move_clock);
+    /// assert!(Board::from_fen_relaxed("8/8/8/8/8/8/8/8 w").is_err());
+    /// ```
+    #[cfg(feature = "fen")]
+    pub fn from_fen_relaxed(fen: &str) -> Result<Self, String> {
+        let mut items: Vec<_> = fen.split_whitespace().collect();
+        match items.len() {
+            4 => items.extend(&["0", "1"]),
+            5 => items.push("1"),
+            6 => {},
+            _ => return Err("Expected 4 to 6 fields".to_owned())
+        }
+        Self::from_fen(&items.join(" "))
+    }
+
+    /// Parse the arguments of a UCI `position` command: `startpos` or
+    /// `fen <fen>`, optionally followed by `moves` and UCI moves.
+    /// Returns the base board and the moves played from it, each one
+    /// being checked. The move counters of the FEN may be omitted.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let (board, moves) = Board::from_uci_position("startpos moves e2e4 e7e5").unwrap();
+    /// assert_eq!(board, Board::new());
+    /// assert_eq!(moves, vec![Move::quiet(Square::E2, Square::E4),
+    ///                        Move::quiet(Square::E7, Square::E5)]);
+    /// ```
+    #[cfg(feature = "fen")]
+    pub fn from_uci_position(spec: &str) -> Result<(Board, Vec<Move>), String> {
+        let tokens: Vec<_> = spec.split_whitespace().collect();
+        let moves_at = tokens.iter().position(|&t| t == "moves").unwrap_or(tokens.len());
+        let board = match tokens.first() {
+            Some(&"startpos") if moves_at == 1 => Board::new(),
+            Some(&"fen") => Self::from_fen_relaxed(&tokens[1..moves_at].join(" "))?,
+            _ => return Err("Expected `startpos` or `fen` and a FEN".to_owned())
+        };
+        let mut current = board.clone();
+        let mut moves = Vec::new();
+        for uci in tokens.iter().skip(moves_at + 1) {
+            let mv = current.parse_uci(uci)?;
+            current = current.play_move(mv);
+            moves.push(mv);
+        }
+        Ok((board, moves))
+    }
+
+    /// Builds a Board from the grid printed by `Display`: each row starts
+    /// with its rank digit, followed by 8 piece letters or `-` for empty squares.
+    /// Other lines, such as the file labels, are ignored.
+    /// White plays, without castling rights nor en passant.
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// let board = Board::from_ascii(&Board::new().to_string()).unwrap();
+    /// assert_eq!(board.to_string(), Board::new().to_string());
+    /// assert_eq!(board.piece_at(Square::E1), Some(W_KING));
+    /// assert!(Board::from_ascii("8 r n b q k b n r").is_err());
+    /// ```
+    pub fn from_ascii(ascii: &str) -> Result<Self, String> {
+        let mut board = Board{ rights: NO_PLAYERS_RIGHTS, ..Board::default() };
+        let mut ranks_read = 0u8;
+        for line in ascii.lines() {
+            let mut cells = line.split_whitespace();
+            let r = match cells.next().and_then(|label| {
+                let mut chars = label.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Rank::from_char(c).ok(),
+                    _ => None,
+                }
+            }) {
+                Some(r) => r,
+                None => continue, // Not a row of the grid
+            };
+            if ranks_read & (1 << r.0) != 0 {
+                return Err(format!("Rank {} is given twice", r.to_char()));
+            }
+            ranks_read |= 1 << r.0;
+            let cells: Vec<_> = cells.collect();
+            if cells.len() != 8 {
+                return Err(format!("Expected 8 squares on rank {}", r.to_char()));
+            }
+            for (f, cell) in (File::A..=File::H).zip(cells) {
+                let mut chars = cell.chars();
+                match (chars.next(), chars.next()) {
+                    (Some('-'), None) => {},
+                    (Some(c), None) => {
+                        board.add_piece(Piece::try_from(c)?, Square::new(r, f));
+                    },
+                    _ => return Err(format!("Invalid square: `{}`", cell)),
+                }
+            }
+        }
+        if ranks_read != 0xFF {
+            return Err("Expected 8 ranks".to_owned());
+        }
+        board.update_attacks();
         Ok(board)
     }
 
@@ -74,12 +198,58 @@ impl Board {
     ///
     /// ```
     /// use chess_std::Board;
-    /// println!("{}", Board::new().to_fen());
+    /// assert_eq!(Board::new().to_fen(),
+    ///            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
     /// ```
     #[cfg(feature = "fen")]
     pub fn to_fen(&self) -> String {
+        format!(
+            "{} {} {} {} {} {}",
+            self.placement_fen(),
+            self.turn,
+            self.castling_fen(),
+            self.ep_fen(),
+            self.half_move_clock,
+            self.fullmove_number
+        )
+    }
+
+    /// Returns the FEN notation without the clocks, with its first four
+    /// fields, which `Board::from_fen_relaxed` reads back.
+    ///
+    /// ```
+    /// use chess_std::Board;
+    /// let fen = "rnbqkbnr/ppp1pppp/8/8/3pP3/5N2/PPPP1PPP/RNBQKB1R b KQkq e3 0 3";
+    /// let board = Board::from_fen(fen).unwrap();
+    /// let short = board.to_fen_short();
+    /// assert_eq!(short, "rnbqkbnr/ppp1pppp/8/8/3pP3/5N2/PPPP1PPP/RNBQKB1R b KQkq e3");
+    ///
+    /// let parsed = Board::from_fen_relaxed(&short).unwrap();
+    /// assert_eq!(parsed, board);
+    /// assert_eq!(parsed.to_fen_short(), short);
+    /// assert_eq!(parsed.to_fen(), format!("{} 0 1", short));
+    /// ```
+    #[cfg(feature = "fen")]
+    pub fn to_fen_short(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.placement_fen(),
+            self.turn,
+            self.castling_fen(),
+            self.ep_fen()
+        )
+    }
+
+    /// The piece placement field of the FEN notation.
+    ///
+    /// ```
+    /// use chess_std::Board;
+    /// assert_eq!(Board::new().placement_fen(),
+    ///            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR");
+    /// ```
+    #[cfg(feature = "fen")]
+    pub fn placement_fen(&self) -> String {
         let mut s = String::new();
-        // Board
         for r in (Rank::R1..=Rank::R8).rev() {
             let mut num_empty = 0;
             for f in File::A..=File::H {
@@ -100,37 +270,52 @@ impl Board {
                 s.push('/');
             }
         }
-        // Turn
-        s.push_str(&format!(" {} ", &self.turn.to_string()));
-        // Castling rights
+        s
+    }
+
+    /// The castling rights field of the FEN notation, `-` when
+    /// no player can castle.
+    ///
+    /// ```
+    /// use chess_std::Board;
+    /// assert_eq!(Board::new().castling_fen(), "KQkq");
+    /// ```
+    #[cfg(feature = "fen")]
+    pub fn castling_fen(&self) -> String {
         if self.rights == NO_PLAYERS_RIGHTS {
-            s.push('-');
-        } else {
-            for player in &PLAYERS {
-                if !self.has_right(*player, Side::King) {
-                    let pc = Piece{ color: *player, ptype: King };
-                    s.push(pc.to_char());
-                }
-                if !self.has_right(*player, Side::Queen) {
-                    let pc = Piece{ color: *player, ptype: Queen };
-                    s.push(pc.to_char());
-                }
+            return "-".to_owned();
+        }
+        let mut s = String::new();
+        for player in &PLAYERS {
+            if self.has_right(*player, Side::King) {
+                let pc = Piece{ color: *player, ptype: King };
+                s.push(pc.to_char());
+            }
+            if self.has_right(*player, Side::Queen) {
+                let pc = Piece{ color: *player, ptype: Queen };
+                s.push(pc.to_char());
             }
         }
-        // En passant target + clocks
-        s.push_str(&format!(
-            " {} {} {}",
-            if self.ep_target.is_some() {
-                self.ep_target.unwrap().san()
-            } else {
-                "-".to_owned()
-            },
-            self.half_move_clock,
-            self.num_moves_played()
-        )[..]);
         s
     }
 
+    /// The en passant target field of the FEN notation.
+    ///
+    /// ```
+    /// use chess_std::{Board, Move, Square};
+    /// let board = Board::new();
+    /// assert_eq!(board.ep_fen(), "-");
+    /// let board = board.play_move(Move::quiet(Square::E2, Square::E4));
+    /// assert_eq!(board.ep_fen(), "e3");
+    /// ```
+    #[cfg(feature = "fen")]
+    pub fn ep_fen(&self) -> String {
+        match self.ep_target {
+            Some(sq) => sq.san(),
+            None => "-".to_owned(),
+        }
+    }
+
     /// Extend a plain move with additional data as a PGN move.
     /// Keep in mind that this function is slow.
     #[cfg(feature = "pgn")]
@@ -141,16 +326,130 @@ impl Board {
             mv,
             self.type_moved_by(mv),
             self.captured_by(mv).map(|pc| pc.ptype),
-            if next_board.in_checkmate() {
+            if !next_board.in_check() {
+                None
+            } else if next_board.has_no_evasion() {
                 Checkmate
-            } else if next_board.in_check() {
-                Check
             } else {
-                None
+                Check
             }
         )
     }
 
+    /// Whether a legal move checkmates the opponent.
+    /// Unlike `Board::in_checkmate` on the next board, the legal replies
+    /// are only generated when the king cannot step out of check.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let fen = "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4";
+    /// let board = Board::from_fen(fen).unwrap();
+    /// assert!(board.is_mating_move(Move::quiet(Square::H5, Square::F7)));
+    /// assert!(!board.is_mating_move(Move::quiet(Square::C4, Square::F7)));
+    /// assert!(!board.is_mating_move(Move::quiet(Square::H5, Square::H7)));
+    /// ```
+    pub fn is_mating_move(&self, mv: Move) -> bool {
+        let next_board = self.play_move(mv);
+        next_board.in_check() && next_board.has_no_evasion()
+    }
+
+    // Whether the current player, being in check, has no legal move.
+    fn has_no_evasion(&self) -> bool {
+        use crate::attack::of_king;
+        let ksq = self.king_square();
+        let mut escapes = of_king(ksq, self.own_color());
+        if escapes.any(|to| self.is_safe_to_move(ksq, to)) {
+            return false;
+        }
+        // Only the king can answer a double check.
+        self.is_double_check() || self.num_moves() == 0
+    }
+
+    /// The legal moves extended as PGN moves, lazily computed by
+    /// `Board::pgn_move`.
+    /// 
+    /// ```
+    /// use chess_std::Board;
+    /// 
+    /// let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    /// let board = Board::from_fen(fen).unwrap();
+    /// assert_eq!(board.legal_pgn_moves().count(), board.num_moves());
+    /// assert!(board.legal_pgn_moves().any(|mv| mv.to_string() == "Qxf6"));
+    /// ```
+    #[cfg(feature = "pgn")]
+    pub fn legal_pgn_moves(&self) -> impl Iterator<Item = PGNMove> + '_ {
+        self.legal_moves().map(move |mv| self.pgn_move(mv))
+    }
+
+    /// The standard algebraic notation (SAN) of a legal move, with the
+    /// check or checkmate suffix. Like `Board::pgn_move`, this is slow.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::from_fen("r3k3/1P6/8/8/8/8/8/R3K1NR w KQq - 0 1").unwrap();
+    /// assert_eq!(board.to_san(Move::castling(Color::White, Side::Queen)), "O-O-O");
+    /// assert_eq!(board.to_san(Move::promotion(Square::B7, Square::B8, Queen)), "b8=Q+");
+    /// assert_eq!(board.to_san(Move::promotion(Square::B7, Square::A8, Knight)), "bxa8=N");
+    /// assert_eq!(board.to_san(Move::quiet(Square::A1, Square::A8)), "Rxa8+");
+    /// assert_eq!(board.to_san(Move::quiet(Square::G1, Square::F3)), "Nf3");
+    /// 
+    /// let board = Board::from_fen("4k3/8/8/R7/8/5N2/8/RN2K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.to_san(Move::quiet(Square::B1, Square::D2)), "Nbd2");
+    /// assert_eq!(board.to_san(Move::quiet(Square::A1, Square::A3)), "R1a3");
+    /// ```
+    #[cfg(feature = "pgn")]
+    pub fn to_san(&self, mv: Move) -> String {
+        let pgn_mv = self.pgn_move(mv);
+        let mut s = String::new();
+        if let Castling(side) = mv.flag {
+            s.push_str(match side {
+                Side::King  => "O-O",
+                Side::Queen => "O-O-O",
+            });
+        } else {
+            let cap_s = if pgn_mv.capture.is_some() { "x" } else { "" };
+            if pgn_mv.ptype == Pawn {
+                if pgn_mv.capture.is_some() {
+                    s.push(mv.from.file().to_char());
+                }
+            } else {
+                s.push(pgn_mv.ptype.to_char());
+                s.push_str(&self.san_disambiguation(mv, pgn_mv.ptype));
+            }
+            s.push_str(cap_s);
+            s.push_str(&mv.to.san());
+            if let Some(new) = mv.promotion_piece() {
+                s.push('=');
+                s.push(new.to_char());
+            }
+        }
+        s.push_str(&pgn_mv.check.to_string());
+        s
+    }
+
+    // The file and/or rank of the origin, when other pieces of the same type
+    // can reach the same destination.
+    #[cfg(feature = "pgn")]
+    fn san_disambiguation(&self, mv: Move, ptype: PieceType) -> String {
+        let others: Vec<Square> = self.legal_moves_of(ptype)
+            .filter(|other| other.to == mv.to && other.from != mv.from)
+            .map(|other| other.from)
+            .collect();
+        if others.is_empty() || ptype == King {
+            String::new()
+        } else if others.iter().all(|sq| sq.file() != mv.from.file()) {
+            mv.from.file().to_string()
+        } else if others.iter().all(|sq| sq.rank() != mv.from.rank()) {
+            mv.from.rank().to_string()
+        } else {
+            mv.from.san()
+        }
+    }
+
     /// Returns a generator over the legal moves.
     pub fn legal_moves(&self) -> MoveGen {
         MoveGen::new_from(self)
@@ -164,6 +463,76 @@ impl Board {
         gen
     }
 
+    /// Returns a generator over the legal moves to a square,
+    /// using `Board::legal_moves()`.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// // The knight can be captured by the pawn, the knight and the queen.
+    /// let board = Board::from_fen("4k3/8/8/4n3/3P4/5N2/4Q3/4K3 w - - 0 1").unwrap();
+    /// let mut froms: Vec<_> = board.legal_moves_to(Square::E5).map(|mv| mv.from).collect();
+    /// froms.sort();
+    /// assert_eq!(froms, vec![Square::E2, Square::F3, Square::D4]);
+    /// 
+    /// // Every promotion is included.
+    /// let board = Board::from_fen("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    /// assert_eq!(board.legal_moves_to(Square::B8).len(), 4);
+    /// assert_eq!(board.legal_moves_to(Square::B8).count(), 4);
+    /// ```
+    pub fn legal_moves_to(&self, sq: Square) -> MoveGenMasked {
+        let mut gen = MoveGenMasked::from(self.legal_moves());
+        gen.set_destination_mask(bit::single(sq));
+        gen
+    }
+
+    /// Returns a generator over the legal moves from a set of squares
+    /// to another set, using `Board::legal_moves()`.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::{Board, bit};
+    /// 
+    /// // The rook moves onto the 7th rank.
+    /// let board = Board::from_fen("4k3/8/8/2p5/8/8/8/R1R1K3 w - - 0 1").unwrap();
+    /// let gen = board.legal_moves_masked(board.piece(W_ROOK), bit::RANK_7);
+    /// assert_eq!(gen.len(), 1);
+    /// let moves: Vec<_> = gen.collect();
+    /// assert_eq!(moves, vec![Move::quiet(Square::A1, Square::A7)]);
+    /// 
+    /// let expected = board.legal_moves()
+    ///     .filter(|mv| bit::W_RANKS.get(mv.from) && !bit::W_RANKS.get(mv.to))
+    ///     .count();
+    /// let gen = board.legal_moves_masked(bit::W_RANKS, !bit::W_RANKS);
+    /// assert_eq!(gen.len(), expected);
+    /// assert_eq!(gen.count(), expected);
+    /// ```
+    pub fn legal_moves_masked(&self, orig: Bitboard, dest: Bitboard) -> MoveGenMasked {
+        let mut gen = MoveGenMasked::from(self.legal_moves());
+        gen.set_origin_mask(orig);
+        gen.set_destination_mask(dest);
+        gen
+    }
+
+    /// Returns the legal en passant captures, at most two.
+    /// The capturing pawn and the captured one may not leave the king
+    /// in check, even when both are between the king and a slider.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::from_fen("4k3/8/8/2PpP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    /// assert_eq!(board.can_en_passant().len(), 2);
+    /// // The rook would attack the king along the rank.
+    /// let board = Board::from_fen("8/8/8/KPp4r/8/8/8/7k w - c6 0 1").unwrap();
+    /// assert!(board.can_en_passant().is_empty());
+    /// ```
+    pub fn can_en_passant(&self) -> Vec<Move> {
+        MoveGen::en_passants_of(self).collect()
+    }
+
     /// Returns a masked generator over the capturing moves,
     /// using `Board::legal_moves()`.
     pub fn legal_captures(&self) -> MoveGenMasked {
@@ -172,11 +541,17 @@ impl Board {
         gen
     }
 
+    /// Returns the captures that do not lose material,
+    /// with a non-negative static exchange evaluation.
+    pub fn winning_captures(&self) -> Vec<Move> {
+        self.legal_captures().filter(|&mv| self.see_ge(mv, 0)).collect()
+    }
+
     /// Returns a masked generator over the legal moves of a piece,
     /// using `Board::legal_moves()`.
     pub fn legal_moves_of(&self, ptype: PieceType) -> MoveGenMasked {
         let mut gen = MoveGenMasked::from(self.legal_moves());
-        gen.set_origin_mask(self.own_piece_type(ptype));
+        gen.set_piece_mask(&[ptype]);
         gen
     }
 
@@ -185,6 +560,98 @@ impl Board {
         self.legal_moves().contains(mv)
     }
 
+    /// Parse a legal move in the UCI long algebraic notation, such as
+    /// `e2e4` or `e7e8q`. A castling is written as a move of the king.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    /// assert_eq!(board.parse_uci("e1g1"), Ok(Move::castling(White, Side::King)));
+    /// assert_eq!(board.parse_uci("b7b8n"), Ok(Move::promotion(Square::B7, Square::B8, Knight)));
+    /// assert!(board.parse_uci("b7b8").is_err());
+    /// assert!(board.parse_uci("e1e3").is_err());
+    /// ```
+    pub fn parse_uci(&self, uci: &str) -> Result<Move, String> {
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return Err(format!("Couldn't parse UCI move: `{}`", uci));
+        }
+        let from = Square::from_san(&uci[0..2])?;
+        let to = Square::from_san(&uci[2..4])?;
+        let promotion = match uci[4..].chars().next() {
+            Some(c) => Some(PieceType::try_from(c.to_ascii_uppercase())?),
+            None => None
+        };
+        self.legal_moves()
+            .find(|mv| mv.from == from && mv.to == to && match mv.flag {
+                Promotion(ptype) => promotion == Some(ptype),
+                _ => promotion.is_none()
+            })
+            .ok_or_else(|| format!("No legal move {} in this position", uci))
+    }
+
+    /// Whether a move fits the piece on its origin, its flag and the
+    /// current occupancy, without generating the legal moves. This is meant
+    /// to quickly reject moves of another position, e.g. hashed moves.
+    /// 
+    /// This does not check whether the king is left in check, nor pins,
+    /// so a pseudo legal move must still pass a king safety test such as
+    /// `Board::is_move_legal` before being played. Castlings are the
+    /// exception: the squares crossed by the king are checked here.
+    /// 
+    /// ```
+    /// use chess_std::{Board, Move, Square};
+    /// 
+    /// let board = Board::new();
+    /// assert!(board.is_pseudo_legal(Move::quiet(Square::G1, Square::F3)));
+    /// assert!(!board.is_pseudo_legal(Move::quiet(Square::G1, Square::G3)));
+    /// ```
+    pub fn is_pseudo_legal(&self, mv: Move) -> bool {
+        use MoveFlag::*;
+        use crate::attack::*;
+        if mv.is_none() || !mv.is_valid(self.turn) || self.own_color().get(mv.to) {
+            return false;
+        }
+        let ptype = match self.piece_at(mv.from) {
+            Some(pc) if pc.color == self.turn => pc.ptype,
+            _ => return false,
+        };
+        let occupied = self.occupied();
+        let enemy = self.opponent_color();
+        let pawn_dests = || {
+            pawn_pushes(self.turn, mv.from, occupied) | of_pawn(self.turn, mv.from, enemy)
+        };
+        match mv.flag {
+            Quiet => match ptype {
+                Pawn   => mv.to.rank() != Rank::last(self.turn) && pawn_dests().get(mv.to),
+                Knight => of_knight(mv.from, bit::EMPTY).get(mv.to),
+                Bishop => of_bishop(mv.from, bit::EMPTY, occupied).get(mv.to),
+                Rook   => of_rook(mv.from, bit::EMPTY, occupied).get(mv.to),
+                Queen  => of_queen(mv.from, bit::EMPTY, occupied).get(mv.to),
+                King   => of_king(mv.from, bit::EMPTY).get(mv.to),
+            },
+            Promotion(_) => ptype == Pawn && pawn_dests().get(mv.to),
+            // Matched by origin and destination, as in `is_move_legal`.
+            EnPassant(_) => {
+                ptype == Pawn
+                && self.ep_target == Some(mv.to)
+                && of_pawn(self.turn, mv.from, bit::FULL).get(mv.to)
+            },
+            Castling(side) => {
+                let (rook_from, rook_to) = Move::rook_castling_coords(self.turn, side);
+                let path = fill_between(mv.from, rook_from);
+                ptype == King
+                && self.has_right(self.turn, side)
+                && self.own_piece_type(Rook).get(rook_from)
+                && !occupied.intersects(path)
+                && !self.in_check()
+                && self.is_safe(rook_to, self.turn)
+                && self.is_safe(mv.to, self.turn)
+            },
+        }
+    }
+
     /// The number of legal moves, using `Board::legal_moves()`.
     /// Promotions are counted for each piece.
     /// 
@@ -216,7 +683,9 @@ impl Board {
         self.move_piece(moved, mv.from, mv.to);
         match mv.flag {
             Quiet => {},
-            EnPassant(pawn_sq) => {
+            EnPassant(_) => {
+                // The passed square of a matching move may be inexact.
+                let pawn_sq = mv.to.shift(-Direction::of_pawns(self.turn));
                 let pawn = Piece{ color: self.turn.opponent(), ptype: Pawn };
                 assert_eq!(Some(pawn), self.piece_at(pawn_sq),
                            "Illegal en passant of a non-pawn piece: {}", pawn);
@@ -239,10 +708,14 @@ impl Board {
             }
         }
         if self.turn == Black {
-            self.half_move_clock += 1;
+            self.fullmove_number += 1;
         }
         self.turn = self.turn.opponent();
-        self.update_attacks();        
+        self.update_attacks();
+        #[cfg(debug_assertions)]
+        if let Err(e) = self.check_invariants() {
+            panic!("Inconsistent board after {}: {}", mv, e);
+        }
     }
 
     /// Returns the subsequent board after applying the move.
@@ -260,6 +733,79 @@ impl Board {
         next_board
     }
 
+    /// Apply the move in place if it is legal, using `Board::is_move_legal`.
+    /// Returns an error otherwise, and the board is left untouched.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let mut board = Board::new();
+    /// assert!(board.try_apply_move(Move::quiet(Square::E2, Square::E5)).is_err());
+    /// assert_eq!(board, Board::new());
+    /// assert!(board.try_apply_move(Move::quiet(Square::E2, Square::E4)).is_ok());
+    /// assert_eq!(board.turn, Black);
+    /// ```
+    pub fn try_apply_move(&mut self, mv: Move) -> Result<(), String> {
+        if !self.is_move_legal(mv) {
+            return Err(format!("Illegal move: {}", mv));
+        }
+        self.apply_move(mv);
+        Ok(())
+    }
+
+    /// Pass the turn without moving, for null-move pruning.
+    /// The en passant target is cleared and the clocks are left unchanged.
+    /// 
+    /// Returns an error when the king is checked.
+    /// 
+    /// ```
+    /// use chess_std::prelude::*;
+    /// use chess_std::Board;
+    /// 
+    /// let mut board = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+    /// let hash = board.zobrist_hash();
+    /// let undo = board.make_null_move().unwrap();
+    /// assert_eq!(board.turn, White);
+    /// assert_eq!(board.en_passant_target(), None);
+    /// assert_ne!(board.zobrist_hash(), hash);
+    /// board.unmake_null_move(undo);
+    /// assert_eq!(board.zobrist_hash(), hash);
+    /// 
+    /// // Two null moves give back the position, without the en passant target.
+    /// let mut board = Board::new();
+    /// let hash = board.zobrist_hash();
+    /// board.make_null_move().unwrap();
+    /// board.make_null_move().unwrap();
+    /// assert_eq!(board.zobrist_hash(), hash);
+    /// 
+    /// let mut board = Board::from_fen("4k3/8/8/8/8/8/8/r3K3 w - - 0 1").unwrap();
+    /// assert!(board.make_null_move().is_err());
+    /// ```
+    pub fn make_null_move(&mut self) -> Result<NullUndo, String> {
+        if self.in_check() {
+            return Err("Cannot pass the turn while in check".to_owned());
+        }
+        let undo = NullUndo {
+            ep_target: self.ep_target.take(),
+            checkers: self.checkers,
+            pinned: self.pinned,
+            pinners: self.pinners,
+        };
+        self.turn = self.turn.opponent();
+        self.update_attacks();
+        Ok(undo)
+    }
+
+    /// Take back a null move played by `Board::make_null_move`.
+    pub fn unmake_null_move(&mut self, undo: NullUndo) {
+        self.turn = self.turn.opponent();
+        self.ep_target = undo.ep_target;
+        self.checkers = undo.checkers;
+        self.pinned = undo.pinned;
+        self.pinners = undo.pinners;
+    }
+
     // Update the castling rights, the en passant target and the last capture/push
     // according to a move that's going to be played.
     #[inline]
@@ -284,7 +830,9 @@ impl Board {
         };
 
         if self.captured_by(mv).is_some() || moved == Pawn {
-           self.last_cap_or_push = self.num_moves_played();
+            self.half_move_clock = 0;
+        } else {
+            self.half_move_clock += 1;
         }
     }
 
@@ -318,6 +866,20 @@ impl Board {
         self.num_moves() == 0
     }
 
+    /// Whether the current player's king is checkmated,
+    /// given the generator of this board's legal moves.
+    #[inline]
+    pub fn is_checkmate(&self, gen: &MoveGen) -> bool {
+        gen.len() == 0 && self.in_check()
+    }
+
+    /// Whether the current player's king is stuck in stalemate,
+    /// given the generator of this board's legal moves.
+    #[inline]
+    pub fn is_stalemate(&self, gen: &MoveGen) -> bool {
+        gen.len() == 0 && !self.in_check()
+    }
+
     /// A theorical evaluation whether there aren't enough pieces to win.
     /// 
     /// ```
@@ -333,20 +895,16 @@ impl Board {
     /// assert!(board.is_material_insufficient());
     /// ```
     pub fn is_material_insufficient(&self) -> bool {
+        let bishops = self.piece_type(Bishop);
         match self.occupied().pop_count() {
             2 => true, // King vs King
-            3 => {
-                self.piece_type(Knight).pop_count() == 1 ||
-                self.piece_type(Bishop).pop_count() == 1
-            },
-            4 => {
-                let w_b = self.of_color_and_type(White, Bishop);
-                let b_b = self.of_color_and_type(Black, Bishop);
-                // Only two bishops on squares of the ours color
-                w_b.pop_count() == 1 && b_b.pop_count() == 1 &&
-                w_b.scan_forward().is_dark() == b_b.scan_forward().is_dark()
+            3 if self.piece_type(Knight).is_populated() => true,
+            _ => {
+                // Only kings and bishops, all on squares of the same color
+                (self.piece_type(King) | bishops) == self.occupied() &&
+                (!bishops.intersects(bit::DARK_SQUARES) ||
+                 !bishops.intersects(bit::LIGHT_SQUARES))
             }
-            _ => false
         }
     }
 
@@ -355,7 +913,7 @@ impl Board {
         use DrawType::*;
         match dt {
             Agreement => true,
-            FiftyMoveRule => self.num_moves_played() - self.last_cap_or_push > 50,
+            FiftyMoveRule => self.half_move_clock >= 100,
             InsufficientMaterial => self.is_material_insufficient(),
             Stalemate => false, // Cannot claim stalemate
             ThreefoldRepetition => false // Don't handle this
@@ -376,13 +934,26 @@ impl Board {
     /// 
     /// This does recompute the number of legal moves.
     pub fn get_result(&self) -> GameResult {
+        self.result_from_moves(&self.legal_moves())
+    }
+
+    /// See `Board::get_result`, given the generator of this board's legal
+    /// moves so that they are not generated again.
+    /// 
+    /// ```
+    /// use chess_std::{Board, GameResult};
+    /// 
+    /// let board = Board::new();
+    /// let gen = board.legal_moves();
+    /// assert_eq!(board.result_from_moves(&gen), GameResult::NoResult);
+    /// assert_eq!(gen.len(), 20);
+    /// ```
+    pub fn result_from_moves(&self, gen: &MoveGen) -> GameResult {
         use {GameResult::*, WinType::*, DrawType::*};
-        if self.is_finished() {
-            if self.in_check() {
-                Win(self.turn.opponent(), Checkmate)
-            } else {
-                Draw(Stalemate)
-            } 
+        if self.is_checkmate(gen) {
+            Win(self.turn.opponent(), Checkmate)
+        } else if self.is_stalemate(gen) {
+            Draw(Stalemate)
         } else if self.can_claim_draw_with(FiftyMoveRule) {
             Draw(FiftyMoveRule)
         } else if self.can_claim_draw_with(InsufficientMaterial) {
@@ -391,6 +962,26 @@ impl Board {
             NoResult
         }
     }
+
+    /// Whether the game goes on, is over, or may be drawn by a claim,
+    /// generating the legal moves only once. This follows
+    /// `Board::result_from_moves`, where a draw other than stalemate
+    /// is only claimable.
+    ///
+    /// ```
+    /// use chess_std::{Board, GameStatus};
+    ///
+    /// assert_eq!(Board::new().status(), GameStatus::Ongoing);
+    /// ```
+    pub fn status(&self) -> GameStatus {
+        let gen = self.legal_moves();
+        match self.result_from_moves(&gen) {
+            GameResult::Win(winner, _) => GameStatus::Checkmate(winner),
+            GameResult::Draw(DrawType::Stalemate) => GameStatus::Stalemate,
+            GameResult::Draw(draw) => GameStatus::DrawClaimable(draw),
+            GameResult::NoResult => GameStatus::Ongoing,
+        }
+    }
 }
 
 
@@ -46,6 +46,12 @@ impl Color {
     pub(crate) fn index(self) -> usize {
         self as usize
     }
+
+    /// The color of an index, or `None` when out of range.
+    #[inline]
+    pub fn from_index(i: usize) -> Option<Color> {
+        PLAYERS.get(i).copied()
+    }
 }
 
 char_enum_conversions! {
@@ -105,6 +111,12 @@ impl PieceType {
     pub(crate) fn index(self) -> usize {
         self as usize
     }
+
+    /// The piece type of an index, or `None` when out of range.
+    #[inline]
+    pub fn from_index(i: usize) -> Option<PieceType> {
+        ALL_PIECE_TYPES.get(i).copied()
+    }
 }
 
 char_enum_conversions! {
@@ -236,6 +248,15 @@ impl TryFrom<char> for Piece {
     }
 }
 
+/// The FEN letter of the piece, uppercase for White.
+/// 
+/// ```
+/// use chess_std::prelude::*;
+/// 
+/// assert_eq!(W_KNIGHT.to_string(), "N");
+/// assert_eq!(B_QUEEN.to_string(), "q");
+/// assert_eq!(format!("{:?}", B_QUEEN), "Piece { color: Black, ptype: Queen }");
+/// ```
 impl fmt::Display for Piece {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.to_char())
@@ -262,6 +283,18 @@ impl Rank {
     
     pub const NUM: usize = 8;
 
+    /// A rank from its value, `None` when it is out of the board.
+    /// 
+    /// ```
+    /// use chess_std::Rank;
+    /// 
+    /// assert_eq!(Rank::new(0), Some(Rank::R1));
+    /// assert_eq!(Rank::new(8), None);
+    /// ```
+    pub fn new(n: u8) -> Option<Self> {
+        if (n as usize) < Self::NUM { Some(Self(n)) } else { None }
+    }
+
     /// Assuming this rank is in White's perspective, this returns
     /// a vertically flipped rank for Black, and is a no-op for White.
     /// 
@@ -356,6 +389,18 @@ impl File {
     
     pub const NUM: usize = 8;
 
+    /// A file from its value, `None` when it is out of the board.
+    /// 
+    /// ```
+    /// use chess_std::File;
+    /// 
+    /// assert_eq!(File::new(7), Some(File::H));
+    /// assert_eq!(File::new(9), None);
+    /// ```
+    pub fn new(n: u8) -> Option<Self> {
+        if (n as usize) < Self::NUM { Some(Self(n)) } else { None }
+    }
+
     /// Convert the file to its corresponding lowercase letter.
     #[inline]
     pub fn to_char(self) -> char {
@@ -461,6 +506,18 @@ impl Square {
         (self.file().0 as i32 - other.file().0 as i32).abs() as usize
     }
 
+    /// Returns the number of king moves between two squares.
+    /// See `Square::king_distance_table` for a faster lookup.
+    /// ```
+    /// use chess_std::Square;
+    /// assert_eq!(Square::A1.distance(Square::H8), 7);
+    /// assert_eq!(Square::G4.distance(Square::D2), 3);
+    /// ```
+    #[inline]
+    pub fn distance(self, other: Self) -> usize {
+        std::cmp::max(self.rank_distance(other), self.file_distance(other))
+    }
+
     /// Assuming this square is in White's perspective, this returns
     /// a vertically flipped square for Black, and is a no-op for White.
     /// ```
@@ -522,18 +579,39 @@ impl Square {
         }
     }
 
-    /// Shift this `Square` in a direction. This operation might fail
-    /// when the square is near to the edge.
+    /// The square a number of files and ranks away from this one,
+    /// or `None` when it would leave the board.
+    /// 
+    /// ```
+    /// use chess_std::Square;
+    /// 
+    /// assert_eq!(Square::G1.offset(-1, 2), Some(Square::F3));
+    /// assert_eq!(Square::H1.offset(1, 0), None);
+    /// ```
+    #[inline]
+    pub fn offset(self, d_file: i8, d_rank: i8) -> Option<Square> {
+        let file = self.file().0 as i8 + d_file;
+        let rank = self.rank().0 as i8 + d_rank;
+        if (0..8).contains(&file) && (0..8).contains(&rank) {
+            Some(Square(((rank << 3) + file) as u8))
+        } else {
+            None
+        }
+    }
+
+    /// Shift this `Square` in a direction. The result is `Square::NONE`
+    /// when the square is on the edge.
     /// 
     /// ```
     /// use chess_std::{Square, Direction};
     /// 
-    /// println!("{}", Square::H1.shift(Direction::East)); // Prints `a2`
+    /// assert_eq!(Square::E4.shift(Direction::NorthWest), Square::D5);
+    /// assert_eq!(Square::H1.shift(Direction::East), Square::NONE);
     /// ```
     #[inline]
     pub fn shift(self, dir: Direction) -> Square {
-        let shifted = self.0 as i8 + dir as i8;
-        if shifted > 0 { Square(shifted as u8) } else { Square::NONE }
+        let (d_file, d_rank) = dir.deltas();
+        self.offset(d_file, d_rank).unwrap_or(Square::NONE)
     }
 
     /// This swaps the view of the players.
@@ -567,6 +645,14 @@ impl Square {
     }
 }
 
+/// Both the display and the debug form of a square are its SAN.
+/// 
+/// ```
+/// use chess_std::Square;
+/// 
+/// assert_eq!(Square::E4.to_string(), "e4");
+/// assert_eq!(format!("{:?}", Square::E4), "e4");
+/// ```
 impl fmt::Debug for Square {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.san())
@@ -631,6 +717,34 @@ impl Direction {
             NoDir      => 8
         }
     }
+
+    /// Whether this is a bishop direction.
+    #[inline]
+    pub fn is_diagonal(self) -> bool {
+        matches!(self, NorthWest | NorthEast | SouthWest | SouthEast)
+    }
+
+    /// Whether this is a rook direction.
+    #[inline]
+    pub fn is_orthogonal(self) -> bool {
+        matches!(self, North | South | East | West)
+    }
+
+    /// The file and rank steps of a direction.
+    #[inline]
+    pub fn deltas(self) -> (i8, i8) {
+        match self {
+            North  => ( 0,  1),
+            South  => ( 0, -1),
+            East   => ( 1,  0),
+            West   => (-1,  0),
+            NorthWest  => (-1,  1),
+            NorthEast  => ( 1,  1),
+            SouthWest  => (-1, -1),
+            SouthEast  => ( 1, -1),
+            NoDir      => ( 0,  0)
+        }
+    }
 }
 
 
@@ -667,3 +781,55 @@ fn test_char_conversions() {
     assert_eq!(Piece::try_from('P'),     Ok(W_PAWN));
     assert_eq!(Piece::try_from('r'),     Ok(B_ROOK));
 }
+
+#[test]
+fn test_index_conversions() {
+    use crate::moves::castling::Side;
+    for col in &PLAYERS {
+        assert_eq!(Color::from_index(col.index()), Some(*col));
+    }
+    assert_eq!(Color::from_index(NUM_PLAYERS), None);
+    for ptype in &ALL_PIECE_TYPES {
+        assert_eq!(PieceType::from_index(ptype.index()), Some(*ptype));
+    }
+    assert_eq!(PieceType::from_index(NUM_PIECE_TYPES), None);
+    for side in &[Side::King, Side::Queen] {
+        assert_eq!(Side::from_index(side.index()), Some(*side));
+    }
+    assert_eq!(Side::from_index(2), None);
+}
+
+#[test]
+fn test_offset() {
+    // Corners
+    assert_eq!(Square::A1.offset(0, 0), Some(Square::A1));
+    assert_eq!(Square::A1.offset(7, 7), Some(Square::H8));
+    assert_eq!(Square::A1.offset(-1, 0), None);
+    assert_eq!(Square::A1.offset(0, -1), None);
+    assert_eq!(Square::H8.offset(1, 0), None);
+    assert_eq!(Square::H8.offset(0, 1), None);
+    assert_eq!(Square::H1.offset(1, 1), None);
+    assert_eq!(Square::A8.offset(-7, -7), None);
+    assert_eq!(Square::A8.offset(7, -7), Some(Square::H1));
+    // Knight L-shapes, which never wrap around the board
+    assert_eq!(Square::B1.offset(-1, 2), Some(Square::A3));
+    assert_eq!(Square::B1.offset(-2, 1), None);
+    assert_eq!(Square::G8.offset(2, -1), None);
+    assert_eq!(Square::G8.offset(-1, -2), Some(Square::F6));
+    // Shifting agrees with the offsets of the direction
+    for dir in &ALL_DIRECTIONS {
+        assert_eq!(Square::A1.shift(*dir) == Square::NONE,
+                   !matches!(dir, North | East | NorthEast));
+        assert_eq!(Square::H8.shift(*dir) == Square::NONE,
+                   !matches!(dir, South | West | SouthWest));
+    }
+}
+
+#[test]
+fn test_king_distance_table() {
+    for a in Square::A1..=Square::H8 {
+        for b in Square::A1..=Square::H8 {
+            assert_eq!(a.king_distance_table(b) as usize, a.distance(b), "{} {}", a, b);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use wasm_bindgen::prelude::*;
+
+use chess_std as cs;
+use crate::units::Square;
+
+
+/// A set of squares, one bit per square from A1 to H8.
+/// The raw value can be read as a `BigInt` to do set operations in JS.
+#[wasm_bindgen]
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct Bitboard(pub (crate) cs::Bitboard);
+
+#[wasm_bindgen]
+impl Bitboard {
+
+    pub fn copy(&self) -> Self {
+        Self(self.0)
+    }
+
+    pub fn equals(&self, rhs: &Bitboard) -> bool {
+        *self == *rhs
+    }
+
+    /// A set from its raw value.
+    pub fn fromValue(value: u64) -> Self {
+        Self(cs::Bitboard(value))
+    }
+
+    /// The raw value of this set.
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> u64 {
+        self.0.0
+    }
+
+    /// Whether a square is in the set.
+    pub fn get(&self, sq: &Square) -> bool {
+        self.0.get(sq.cs())
+    }
+
+    /// The number of squares in the set.
+    pub fn popCount(&self) -> u32 {
+        self.0.pop_count()
+    }
+
+    /// The squares of the set, from A1 to H8.
+    pub fn squares(&self) -> Vec<u8> {
+        self.0.squares().into_iter().map(u8::from).collect()
+    }
+
+    /// Return an ASCII board diagram of the set.
+    pub fn toString(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod bit_test {
+    use wasm_bindgen_test::*;
+    use super::*;
+    use crate::units::{Color, Piece, PieceType};
+    use crate::position::Board;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_pawns_pop_count() {
+        let board = Board::new();
+        let pawn = PieceType(cs::Pawn);
+        let white = board.pieceBitboard(&Piece::new(&Color(cs::White), &pawn));
+        let black = board.pieceBitboard(&Piece::new(&Color(cs::Black), &pawn));
+        assert_eq!(white.popCount(), 8);
+        let pawns = Bitboard::fromValue(white.value() | black.value());
+        assert_eq!(pawns.popCount(), 16);
+        assert_eq!(board.colorBitboard(&Color(cs::White)).popCount(), 16);
+        assert!(pawns.get(&Square::fromSan("e7").unwrap()));
+        assert!(!pawns.get(&Square::fromSan("e4").unwrap()));
+    }
+}
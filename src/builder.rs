@@ -0,0 +1,58 @@
+use wasm_bindgen::prelude::*;
+
+use chess_std as cs;
+use crate::units::{Color, Piece, Square};
+use crate::moves::CastlingSide;
+use crate::position::Board;
+
+
+/// A board builder, to setup a `Board` from a custom position.
+///
+/// Each method consumes the builder and returns it, so that calls can be chained:
+/// `new Builder().piece(wKing, a2).piece(bKing, b4).turn(black).build()`.
+#[wasm_bindgen]
+pub struct Builder(cs::board::Builder);
+
+#[wasm_bindgen]
+impl Builder {
+
+    /// Start with an empty position.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self(cs::board::Builder::new())
+    }
+
+    /// Start with the pieces, the turn and the half-move clock of a board.
+    pub fn fromBoard(board: &Board) -> Self {
+        Self(cs::board::Builder::from(board.0.clone()))
+    }
+
+    /// Add a piece at a square.
+    pub fn piece(mut self, pc: &Piece, sq: &Square) -> Self {
+        self.0.piece(pc.0, sq.cs());
+        self
+    }
+
+    /// Set the turn.
+    pub fn turn(mut self, col: &Color) -> Self {
+        self.0.turn(col.0);
+        self
+    }
+
+    /// Set the half-move clock.
+    pub fn halfMoveClock(mut self, hmc: u32) -> Self {
+        self.0.half_move_clock(hmc);
+        self
+    }
+
+    /// Set a castling right for a player and a side.
+    pub fn castlingRight(mut self, player: &Color, side: CastlingSide) -> Self {
+        self.0.castling_right(player.0, side.cs());
+        self
+    }
+
+    /// Returns the board if it is valid, else `undefined`.
+    pub fn build(&self) -> Option<Board> {
+        self.0.build().map(Board)
+    }
+}
@@ -0,0 +1,191 @@
+// Mating knowledge for the basic endgames, where a shallow search wanders.
+
+use chess_std as cs;
+use cs::prelude::*;
+
+// The room of the lone king from which mates are searched one move deeper.
+const MATING_ROOM: u8 = 6;
+
+
+/// A move mating with a king and a queen or a rook against a lone king,
+/// when the player to move has them. Past a short search for a mate,
+/// it squeezes the lone king between the lines of the heavy piece,
+/// brings the king up to support it, and never leaves the piece en prise
+/// nor stalemates. Returns `None` for any other material.
+pub fn try_kxk_move(board: &cs::Board) -> Option<cs::Move> {
+    let signature = board.material_signature();
+    let (white, black) = signature.split_once('v')?;
+    let (strong, weak) = match board.turn {
+        White => (white, black),
+        Black => (black, white),
+    };
+    if !matches!(strong, "KQ" | "KR") || weak != "K" {
+        return None;
+    }
+
+    let now = Cutoff::from(board);
+    // A deeper search only once the lone king is boxed in, where it is cheap.
+    let depth = if now.room <= MATING_ROOM { 3 } else { 2 };
+    if let Some(mv) = (1..=depth).find_map(|depth| mate_in(board, depth)) {
+        return Some(mv);
+    }
+
+    // The safe moves, which do not stalemate nor leave the piece en prise.
+    let candidates: Vec<(cs::Move, Cutoff)> = board.legal_moves()
+        .filter_map(|mv| {
+            let next = board.play_move(mv);
+            let replies: Vec<cs::Move> = next.legal_moves().collect();
+            if replies.is_empty() || replies.iter().any(|reply| next.captured_by(*reply).is_some()) {
+                return None;
+            }
+            // The king may shield a line of the piece, so the room is
+            // the largest the lone king reaches.
+            let mut cut = Cutoff::from(&next);
+            cut.room = replies.iter()
+                .map(|reply| Cutoff::from(&next.play_move(*reply)).room)
+                .max()?;
+            Some((mv, cut))
+        })
+        .collect();
+
+    let piece_moves = |mv: &cs::Move| board.piece_type_at(mv.from) != Some(King);
+    let holds = |cut: &Cutoff| !cut.exposed && cut.divides && cut.room <= now.room;
+    // Squeeze: the lone king loses room.
+    candidates.iter()
+        .filter(|(mv, cut)| piece_moves(mv) && holds(cut) && cut.room < now.room)
+        .min_by_key(|(_, cut)| cut.room)
+        // Approach: the king comes to support the piece, or to the opposition.
+        .or_else(|| candidates.iter()
+            .filter(|(mv, cut)| !piece_moves(mv) && holds(cut) && cut.critical < now.critical)
+            .min_by_key(|(_, cut)| cut.critical))
+        // Keep the room: a waiting move.
+        .or_else(|| candidates.iter()
+            .filter(|(_, cut)| holds(cut))
+            .min_by_key(|(_, cut)| (cut.room, cut.critical)))
+        // Divide: cut the lone king away from the king.
+        .or_else(|| candidates.iter()
+            .filter(|(_, cut)| !cut.exposed && cut.divides)
+            .min_by_key(|(_, cut)| (cut.room, cut.critical)))
+        .or_else(|| candidates.iter()
+            .min_by_key(|(_, cut)| (cut.exposed, cut.room, cut.critical)))
+        .map(|(mv, _)| *mv)
+}
+
+// A move forcing mate within `depth` moves, if any.
+fn mate_in(board: &cs::Board, depth: u32) -> Option<cs::Move> {
+    board.legal_moves().find(|mv| {
+        let next = board.play_move(*mv);
+        let mut replies = next.legal_moves().peekable();
+        if replies.peek().is_none() {
+            return next.in_check();
+        }
+        depth > 1 && replies.all(|reply| mate_in(&next.play_move(reply), depth - 1).is_some())
+    })
+}
+
+// How the heavy piece cuts the lone king off, after a move of the mating side.
+struct Cutoff {
+    // The area on the side of the lone king of the lines of the piece.
+    room: u8,
+    // Whether the lone king can attack the piece before the king defends it.
+    exposed: bool,
+    // Whether a line of the piece stands between the kings.
+    divides: bool,
+    // The distance of the king to the square it heads for,
+    // then the distance between the kings by files and ranks.
+    critical: (usize, i8),
+}
+
+impl Cutoff {
+    fn from(board: &cs::Board) -> Self {
+        let piece = (board.piece_type(Queen) | board.piece_type(Rook)).scan_forward();
+        let col = board.color_at(piece).unwrap();
+        let king = board.king_square_of(col);
+        let lone_king = board.king_square_of(col.opponent());
+
+        let coords = |sq: cs::Square| (u8::from(sq.file()) as i8, u8::from(sq.rank()) as i8);
+        let (pf, pr) = coords(piece);
+        let (kf, kr) = coords(king);
+        let (lf, lr) = coords(lone_king);
+        let side = |p: i8, l: i8| -> u8 {
+            match l.cmp(&p) {
+                std::cmp::Ordering::Less    => p as u8,
+                std::cmp::Ordering::Greater => 7 - p as u8,
+                std::cmp::Ordering::Equal   => 8,
+            }
+        };
+        let between = |k: i8, p: i8, l: i8| (k <= p && p < l) || (l < p && p <= k);
+        let (files, ranks) = (side(pf, lf), side(pr, lr));
+        // On its last line, the lone king is mated from the opposition,
+        // else the king goes next to the piece to support it.
+        let (cf, cr) = if ranks == 1 {
+            (lf, lr + 2 * (pr - lr).signum())
+        } else if files == 1 {
+            (lf + 2 * (pf - lf).signum(), lr)
+        } else {
+            (pf + (lf - pf).signum(), pr + (lr - pr).signum())
+        };
+        let critical = cs::Square::new(cs::Rank::new(cr as u8).unwrap(), cs::File::new(cf as u8).unwrap());
+        Self {
+            room: files * ranks,
+            exposed: lone_king.distance(piece) < king.distance(piece),
+            divides: between(kf, pf, lf) || between(kr, pr, lr),
+            critical: (king.distance(critical), (kf - lf).abs() + (kr - lr).abs()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod endgame_test {
+    use wasm_bindgen_test::*;
+    use super::*;
+
+    // Plays the mating side with `try_kxk_move` and the lone king with the
+    // reply that keeps it the freest, until mate or the move budget.
+    fn mate_in_budget(fen: &str, budget: usize) -> bool {
+        let mut board = cs::Board::from_fen(fen).unwrap();
+        for _ in 0..budget {
+            let mv = try_kxk_move(&board).unwrap();
+            board = board.play_move(mv);
+            let replies: Vec<cs::Move> = board.legal_moves().collect();
+            if replies.is_empty() {
+                return board.in_check();
+            }
+            let reply = replies.into_iter()
+                .max_by_key(|reply| Cutoff::from(&board.play_move(*reply)).room)
+                .unwrap();
+            board = board.play_move(reply);
+        }
+        false
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_rook_mates() {
+        for fen in [
+            "8/8/8/3k4/8/8/8/R3K3 w - - 0 1",
+            "8/8/8/8/4k3/8/8/K6R w - - 0 1",
+            "7K/8/8/8/2k5/8/8/r7 b - - 0 1",
+            "8/2R5/8/8/5k2/8/1K6/8 w - - 0 1",
+        ] {
+            assert!(mate_in_budget(fen, 50), "{}", fen);
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_queen_mates() {
+        assert!(mate_in_budget("8/8/8/3k4/8/8/8/Q3K3 w - - 0 1", 50));
+        assert!(mate_in_budget("1q6/8/4k3/8/8/8/8/7K b - - 0 1", 50));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_other_material() {
+        let board = cs::Board::new();
+        assert_eq!(try_kxk_move(&board), None);
+        // The lone king is to move.
+        let board = cs::Board::from_fen("8/8/8/3k4/8/8/8/R3K3 b - - 0 1").unwrap();
+        assert_eq!(try_kxk_move(&board), None);
+        let board = cs::Board::from_fen("8/8/8/3k4/8/8/8/R2BK3 w - - 0 1").unwrap();
+        assert_eq!(try_kxk_move(&board), None);
+    }
+}
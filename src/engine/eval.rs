@@ -0,0 +1,242 @@
+// A default evaluation: material and piece-square tables.
+
+use wasm_bindgen::prelude::*;
+
+use chess_std as cs;
+use cs::prelude::*;
+use crate as wasm;
+
+
+// The piece-square tables, from White's point of view,
+// from A1 to H8 (the first row is the first rank).
+const PAWN_TABLE: Grid<i32> = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+      5,  10,  10, -20, -20,  10,  10,   5,
+      5,  -5, -10,   0,   0, -10,  -5,   5,
+      0,   0,   0,  20,  20,   0,   0,   0,
+      5,   5,  10,  25,  25,  10,   5,   5,
+     10,  10,  20,  30,  30,  20,  10,  10,
+     50,  50,  50,  50,  50,  50,  50,  50,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+const KNIGHT_TABLE: Grid<i32> = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+const BISHOP_TABLE: Grid<i32> = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+const ROOK_TABLE: Grid<i32> = [
+      0,   0,   0,   5,   5,   0,   0,   0,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+     -5,   0,   0,   0,   0,   0,   0,  -5,
+      5,  10,  10,  10,  10,  10,  10,   5,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+const QUEEN_TABLE: Grid<i32> = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+const KING_TABLE: Grid<i32> = [
+     20,  30,  10,   0,   0,  10,  30,  20,
+     20,  20,   0,   0,   0,   0,  20,  20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+];
+
+/// The weights of the optional evaluation terms, in centipawns.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvalParams {
+    /// The bonus per legal move, over the opponent's.
+    pub mobility: i32,
+    /// The penalty per square around the king attacked by the opponent.
+    pub king_attack: i32,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        Self { mobility: 5, king_attack: 15 }
+    }
+}
+
+#[wasm_bindgen]
+impl EvalParams {
+    /// The default weights.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The value of a piece type in centipawns. The king is not counted.
+pub fn centipawns(ptype: PieceType) -> i32 {
+    match ptype {
+        King => 0,
+        _    => ptype.value() as i32 * 100
+    }
+}
+
+/// The value of a piece at a square in a piece-square table written
+/// from White's point of view, as in the tables above. The table is
+/// mirrored for Black, whose values are negated: the result is always
+/// from White's point of view.
+pub fn pst_value(table: &Grid<i32>, pc: Piece, sq: Square) -> i32 {
+    let value = table[sq.relative(pc.color).index()];
+    match pc.color {
+        White => value,
+        Black => -value,
+    }
+}
+
+// The piece-square table of a piece type.
+fn square_table(ptype: PieceType) -> &'static Grid<i32> {
+    match ptype {
+        Pawn   => &PAWN_TABLE,
+        Knight => &KNIGHT_TABLE,
+        Bishop => &BISHOP_TABLE,
+        Rook   => &ROOK_TABLE,
+        Queen  => &QUEEN_TABLE,
+        King   => &KING_TABLE,
+    }
+}
+
+/// The static evaluation of a board in centipawns,
+/// from the point of view of the side to move.
+pub fn eval(board: &cs::Board) -> i32 {
+    let mut score = 0;
+    for pc in &ALL_PIECES {
+        let sign = if pc.color == White { 1 } else { -1 };
+        for sq in board.piece(*pc) {
+            score += sign * centipawns(pc.ptype) + pst_value(square_table(pc.ptype), *pc, sq);
+        }
+    }
+    if board.turn == White { score } else { -score }
+}
+
+// The difference between the number of legal moves of both players.
+// The opponent's moves are counted after a null move, so this is 0 in check.
+fn mobility(board: &cs::Board) -> i32 {
+    let mut passed = board.clone();
+    if passed.make_null_move().is_err() {
+        return 0;
+    }
+    board.num_moves() as i32 - passed.num_moves() as i32
+}
+
+// The number of squares around the king of a player attacked by the opponent.
+fn king_danger(board: &cs::Board, player: Color) -> i32 {
+    let ksq = board.king_square_of(player);
+    let occupied = board.occupied();
+    let enemy = board.color(player.opponent());
+    cs::attack::of_king(ksq, cs::bit::EMPTY)
+        .filter(|sq| board.attackers_to(*sq, occupied).intersects(enemy))
+        .count() as i32
+}
+
+/// The static evaluation with the mobility and the king safety terms,
+/// in centipawns from the point of view of the side to move.
+pub fn eval_with(board: &cs::Board, params: &EvalParams) -> i32 {
+    let me = board.turn;
+    eval(board)
+        + params.mobility * mobility(board)
+        - params.king_attack * (king_danger(board, me) - king_danger(board, me.opponent()))
+}
+
+/// The static evaluation of a board in centipawns,
+/// from the point of view of the side to move.
+#[wasm_bindgen]
+pub fn evaluate(board: &wasm::Board) -> i32 {
+    eval(&board.0)
+}
+
+/// The static evaluation with the mobility and the king safety terms,
+/// in centipawns from the point of view of the side to move.
+#[wasm_bindgen]
+pub fn evaluateWith(board: &wasm::Board, params: &EvalParams) -> i32 {
+    eval_with(&board.0, params)
+}
+
+
+#[cfg(test)]
+mod eval_test {
+    use wasm_bindgen_test::*;
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_eval_start() {
+        assert_eq!(eval(&cs::Board::new()), 0);
+        let board = cs::Board::new().play_move(Move::quiet(Square::E2, Square::E4));
+        assert!(eval(&board).abs() < 100);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_pst_mirror() {
+        for ptype in &ALL_PIECE_TYPES {
+            let table = square_table(*ptype);
+            for sq in [Square::E2, Square::B7, Square::G1, Square::D5] {
+                let white = pst_value(table, Piece{color: White, ptype: *ptype}, sq);
+                let black = pst_value(table, Piece{color: Black, ptype: *ptype}, sq.flip_vertical());
+                assert_eq!(white, -black);
+            }
+        }
+        // The knight is worse on the rim, for both colors.
+        let knight = |col| pst_value(&KNIGHT_TABLE, Piece{color: col, ptype: Knight}, Square::A8);
+        assert_eq!((knight(White), knight(Black)), (-50, 50));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_eval_king_safety() {
+        let params = EvalParams { mobility: 0, ..EvalParams::default() };
+        let safety = |fen| {
+            let board = cs::Board::from_fen(fen).unwrap();
+            eval_with(&board, &params) - eval(&board)
+        };
+        // The same pawns, as a shield or far from the king.
+        let shielded = safety("6k1/1b6/8/8/8/8/5PPP/6K1 w - - 0 1");
+        let exposed = safety("6k1/1b6/8/8/8/8/PPP5/6K1 w - - 0 1");
+        assert!(exposed < shielded);
+        assert_eq!(eval_with(&cs::Board::new(), &EvalParams::default()), 0);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_eval_up_a_rook() {
+        let fen = "1nbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQk - 0 1";
+        let board = cs::Board::from_fen(fen).unwrap();
+        assert!(eval(&board) > 400);
+        let fen = "1nbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQk - 0 1";
+        let board = cs::Board::from_fen(fen).unwrap();
+        assert!(eval(&board) < -400);
+    }
+}
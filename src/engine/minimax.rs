@@ -8,42 +8,91 @@ use crate as wasm;
 
 type Score = i32;
 
-const MIN_SCORE: Score = i32::MAX;
-const MAX_SCORE: Score = i32::MIN;
+// The score of delivering checkmate at the root, lowered by one per ply
+// so that shorter mates are preferred. It stays far from overflowing.
+const MATE_SCORE: Score = 30000;
 const AVG_SCORE: Score = 0;
 
 
+/// The outcome of a search, for analysis display.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct SearchResult {
+    best_move: Option<cs::Move>,
+    score: Score,
+    nodes: u32,
+    depth: u32,
+    elapsed_ms: f64
+}
+
+#[wasm_bindgen]
+impl SearchResult {
+    /// The selected move, `undefined` when the game is already over.
+    #[wasm_bindgen(getter)]
+    pub fn bestMove(&self) -> Option<wasm::Move> {
+        self.best_move.map(wasm::Move::from_cs)
+    }
+
+    /// The score of the best move, from the point of view of the side to move.
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    /// The number of visited positions.
+    #[wasm_bindgen(getter)]
+    pub fn nodes(&self) -> u32 {
+        self.nodes
+    }
+
+    /// The search depth of the engine.
+    #[wasm_bindgen(getter)]
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// The duration of the search, in milliseconds.
+    #[wasm_bindgen(getter)]
+    pub fn elapsedMs(&self) -> f64 {
+        self.elapsed_ms
+    }
+}
+
+
 /// A basic, exhaustive minimax engine.
 #[wasm_bindgen]
 pub struct Minimax {
-    depth: u32
+    depth: u32,
+    contempt: Score
 }
 
 impl Default for Minimax {
     fn default() -> Self {
-        Self { depth: 4 }
+        Self { depth: 4, contempt: 0 }
     }
 }
 
 impl Engine for Minimax {
     fn select_move(&mut self, board: cs::Board) -> Option<cs::Move> {
-        self.move_with_best_score(board, AVG_SCORE, self.depth).0
+        let mut nodes = 0;
+        self.search(board, &mut nodes).0
     }
 }
 
 #[wasm_bindgen]
 impl Minimax {
-    /// Create a new engine from a search depth.
+    /// Create a new engine from a search depth, and optionally a contempt.
     /// 
-    /// It must be an even, non-zero value.
+    /// The depth must be an even, non-zero value.
+    /// A positive contempt makes the engine avoid draws, a negative one seek them.
     #[wasm_bindgen(constructor, catch)]
-    pub fn new(depth: u32) -> Result<Minimax, JsValue> {
+    pub fn new(depth: u32, contempt: Option<Score>) -> Result<Minimax, JsValue> {
         if depth == 0 {
             Err("Cannot have depth-0 minimax engine".into())
         } else if depth % 2 != 0 {
             Err("Search depth must be even".into())
         } else {
-            Ok(Self { depth })
+            Ok(Self { depth, contempt: contempt.unwrap_or(0) })
         }
     }
 
@@ -53,28 +102,73 @@ impl Minimax {
         self.depth
     }
 
+    /// Get the contempt of this engine, the draw penalty for its own side.
+    #[wasm_bindgen(getter)]
+    pub fn contempt(&self) -> Score {
+        self.contempt
+    }
+
     /// Select a move from a board. Returns `undefined` when no move can be selected.
     #[wasm_bindgen]
     pub fn selectMove(&mut self, board: wasm::Board) -> Option<wasm::Move> {
         self.select_move(board.0).map(wasm::Move::from_cs)
     }
 
+    /// Select a move from a board, along with the search information.
+    pub fn selectMoveWithInfo(&mut self, board: wasm::Board) -> SearchResult {
+        let start = js_sys::Date::now();
+        let mut nodes = 0;
+        let (best_move, score) = self.search(board.0, &mut nodes);
+        SearchResult {
+            best_move,
+            score,
+            nodes,
+            depth: self.depth,
+            elapsed_ms: js_sys::Date::now() - start
+        }
+    }
+
+    // The move to play and its score, from the endgame knowledge when it
+    // applies, which visits no node and scores the material edge, or else
+    // from a search at the depth of the engine.
+    fn search(&self, board: cs::Board, nodes: &mut u32) -> (Option<cs::Move>, Score) {
+        if let Some(mv) = super::endgame::try_kxk_move(&board) {
+            return (Some(mv), board.non_pawn_material(board.turn) as Score);
+        }
+        self.move_with_best_score(board, AVG_SCORE, self.depth, 0, nodes)
+    }
+
+    // The score of a draw for the side to move, `ply` half-moves from the root:
+    // the contempt is a penalty for the side that searches from the root,
+    // and a bonus for its opponent.
+    fn draw_score(&self, ply: u32) -> Score {
+        if ply.is_multiple_of(2) {
+            -self.contempt
+        } else {
+            self.contempt
+        }
+    }
+
     // Find the best move to play if any, and the resulting score after playing it.
-    fn move_with_best_score(&self, board: cs::Board,
-                            current_score: Score, depth: u32)
+    // The board is `ply` half-moves away from the root, so that a mate found
+    // closer to the root scores higher. Every visited position is counted in `nodes`.
+    fn move_with_best_score(&self, board: cs::Board, current_score: Score,
+                            depth: u32, ply: u32, nodes: &mut u32)
                             -> (Option<cs::Move>, Score) {
+        *nodes += 1;
         match board.get_result() {
             cs::GameResult::Win(winner, _) => {
+                let mate = MATE_SCORE - ply as Score;
                 return if winner == board.turn {
-                    (None, MAX_SCORE)
+                    (None, mate)
                 } else {
-                    (None, MIN_SCORE)
+                    (None, -mate)
                 };
             },
-            cs::GameResult::Draw(_) => return (None, AVG_SCORE),
+            cs::GameResult::Draw(_) => return (None, self.draw_score(ply)),
             _ => {}
         };
-        if depth < self.depth {
+        if depth == 0 {
             // Return the current positional evaluation.
             return (None, current_score);
         }
@@ -89,14 +183,86 @@ impl Minimax {
             };
             let next_board = board.play_move(mv);
             let (_, best_opponent_score) = self.move_with_best_score(
-                next_board, -next_score, depth - 1);
+                next_board, -next_score, depth - 1, ply + 1, nodes);
             // We want the opposite of our opponent.
             let our_score = -best_opponent_score;
-            if our_score > best_score {
+            if best_move.is_none() || our_score > best_score {
                 best_move = Some(mv);
                 best_score = our_score;
             }
         }
         (best_move, best_score)
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod minimax_test {
+    use wasm_bindgen_test::*;
+    use super::*;
+    use cs::Square;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_contempt() {
+        // Taking the knight leaves a drawn king and knight against king.
+        let board = cs::Board::from_fen("k7/8/8/8/3n4/5N2/8/7K w - - 0 1").unwrap();
+        let capture = cs::Move::quiet(Square::F3, Square::D4);
+        let mut engine = Minimax::new(2, Some(-50)).ok().unwrap();
+        assert_eq!(engine.select_move(board.clone()), Some(capture));
+        let mut engine = Minimax::new(2, Some(50)).ok().unwrap();
+        let mv = engine.select_move(board).unwrap();
+        assert_ne!(mv, capture);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_endgame_knowledge() {
+        // The rook mates without searching, even at the smallest depth.
+        let board = cs::Board::from_fen("8/8/8/3k4/8/8/8/R3K3 w - - 0 1").unwrap();
+        let mut engine = Minimax::new(2, None).ok().unwrap();
+        let expected = crate::engine::endgame::try_kxk_move(&board);
+        assert_eq!(engine.select_move(board.clone()), expected);
+        // The same move with the search information.
+        let mut nodes = 0;
+        assert_eq!(engine.search(board, &mut nodes).0, expected);
+        assert_eq!(nodes, 0);
+    }
+
+    fn search(fen: &str, depth: u32) -> (Option<cs::Move>, Score) {
+        let board = cs::Board::from_fen(fen).unwrap();
+        let engine = Minimax::new(depth, None).ok().unwrap();
+        engine.move_with_best_score(board, AVG_SCORE, depth, 0, &mut 0)
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_mate_scores() {
+        // Rh8 mates at once, and slower mates are also within the depth.
+        let (mv, mate_in_1) = search("k7/8/1K6/8/8/8/8/7R w - - 0 1", 4);
+        assert_eq!(mv, Some(cs::Move::quiet(Square::H1, Square::H8)));
+        assert_eq!(mate_in_1, MATE_SCORE - 1);
+        // Kb6 first, then Rh8 mates.
+        let (_, mate_in_2) = search("k7/8/2K5/8/8/8/8/7R w - - 0 1", 4);
+        assert_eq!(mate_in_2, MATE_SCORE - 3);
+        assert!(mate_in_1 > mate_in_2);
+        // The same mate seen by the defending side.
+        let (_, mated) = search("k7/8/1K6/8/8/8/8/7R b - - 0 1", 4);
+        assert_eq!(mated, -(MATE_SCORE - 2));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_queen_mates() {
+        // The engine plays both sides. Many queen moves keep a mate in 2,
+        // so it must prefer the shortest mates not to play them forever.
+        let board = cs::Board::from_fen("1k6/8/2K5/8/8/8/8/3Q4 w - - 0 1").unwrap();
+        let mut game = cs::Game::from_board(board);
+        // The search is driven directly, past the endgame knowledge.
+        let engine = Minimax::new(4, None).ok().unwrap();
+        while !game.is_finished() && game.moves.len() < 4 {
+            let mut nodes = 0;
+            let (mv, _) = engine.move_with_best_score(
+                game.board().clone(), AVG_SCORE, engine.depth, 0, &mut nodes);
+            game.play_move(mv.unwrap());
+        }
+        assert!(game.in_checkmate(), "{}", game.to_pgn());
+        assert_eq!(game.board().turn, cs::Black);
+    }
+}
@@ -4,6 +4,9 @@
 use chess_std as cs;
 
 pub mod minimax;
+pub mod eval;
+pub mod random;
+pub mod endgame;
 
 
 /// A chess engine searches a move.
@@ -0,0 +1,87 @@
+// A random player, as a baseline opponent and for reproducible game data.
+
+use wasm_bindgen::prelude::*;
+
+use chess_std as cs;
+use super::Engine;
+use crate as wasm;
+
+
+/// An engine that uniformly samples a legal move.
+/// The same seed always produces the same sequence of choices.
+#[wasm_bindgen]
+pub struct RandomEngine {
+    state: u64
+}
+
+impl Engine for RandomEngine {
+    fn select_move(&mut self, board: cs::Board) -> Option<cs::Move> {
+        let mut moves = board.legal_moves();
+        let len = moves.len();
+        if len == 0 {
+            return None;
+        }
+        let index = (self.next_u64() % len as u64) as usize;
+        moves.nth(index)
+    }
+}
+
+#[wasm_bindgen]
+impl RandomEngine {
+    /// Create a new engine from a seed.
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u64) -> RandomEngine {
+        // Xorshift never leaves the zero state.
+        let state = if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed };
+        Self { state }
+    }
+
+    /// Select a move from a board. Returns `undefined` when no move can be selected.
+    pub fn selectMove(&mut self, board: &wasm::Board) -> Option<wasm::Move> {
+        self.select_move(board.0.clone()).map(wasm::Move::from_cs)
+    }
+
+    // The xorshift64 generator.
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+}
+
+
+#[cfg(test)]
+mod random_test {
+    use wasm_bindgen_test::*;
+    use super::*;
+
+    fn random_game(seed: u64) -> cs::Game {
+        let mut engine = RandomEngine::new(seed);
+        let mut game = cs::Game::new();
+        while !game.is_finished() && game.moves.len() < 500 {
+            let mv = engine.select_move(game.board().clone()).unwrap();
+            assert!(game.is_move_legal(mv));
+            game.play_move(mv);
+        }
+        game
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_random_legal() {
+        for seed in 0..10 {
+            let game = random_game(seed);
+            let board = game.board().clone();
+            let no_moves = board.legal_moves().len() == 0;
+            assert_eq!(RandomEngine::new(seed).select_move(board).is_none(), no_moves);
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_random_deterministic() {
+        assert_eq!(random_game(42).moves, random_game(42).moves);
+        assert_ne!(random_game(42).moves, random_game(43).moves);
+    }
+}
@@ -20,6 +20,11 @@ pub struct Game(cs::Game);
 
 #[wasm_bindgen]
 impl Game {
+    /// A copy of the whole game, to explore another line from it.
+    pub fn copy(&self) -> Self {
+        Self(self.0.clone())
+    }
+
     /// A game that starts with the first board.
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
@@ -53,6 +58,27 @@ impl Game {
         self.0.moves.last().copied().map(Move::from_cs)
     }
 
+    /// The SAN of every move played, in order.
+    pub fn sanHistory(&self) -> Vec<String> {
+        self.0.moves_san()
+    }
+
+    /// The board after a number of half-moves, the first board being at 0.
+    /// Returns `undefined` when the game is shorter.
+    pub fn positionAfter(&self, ply: usize) -> Option<Board> {
+        self.0.position_after(ply).cloned().map(Board)
+    }
+
+    /// The FEN after a number of half-moves. Returns `undefined` when the game is shorter.
+    pub fn fenAt(&self, ply: usize) -> Option<String> {
+        self.0.position_after(ply).map(cs::Board::to_fen)
+    }
+
+    /// The FEN of every board in the game, starting with the first one.
+    pub fn fenHistory(&self) -> Vec<String> {
+        self.0.fen_history()
+    }
+
     /// Whether the piece's color at a square is the turn.
     pub fn canSelectSquare(&self, sq: &Square) -> bool {
         self.0.board().color_at(sq.cs()) == Some(self.0.board().turn)
@@ -92,6 +118,16 @@ impl Game {
         self.0.play_move(mv.cs());
     }
 
+    /// Play a move only when it is legal and the game is not finished.
+    /// Returns whether the move was played; the game is left unchanged otherwise.
+    pub fn playMoveChecked(&mut self, mv: &Move) -> bool {
+        if self.0.is_finished() || !self.0.is_move_legal(mv.cs()) {
+            return false;
+        }
+        self.0.play_move(mv.cs());
+        true
+    }
+
     /// Remove the last board and the last move from the list.
     /// The board of the game will then be the previous one.
     pub fn undoLastMove(&mut self) {
@@ -140,18 +176,41 @@ impl Game {
     }
 
     /// Parse a PGN move, playable at this state.
+    /// The error tells why the move was rejected.
     #[wasm_bindgen(catch)]
     pub fn parseMove(&self, pgn: &str) -> Result<Move, JsValue> {
         self.0.parse_move(pgn).map(Move::from_cs)
-            .map_err(|_| js_sys::Error::new("Couldn't parse move").into())
+            .map_err(|err| js_sys::Error::new(&err).into())
+    }
+
+    /// The standard algebraic notation (SAN) of a legal move at this state,
+    /// with the check or checkmate suffix.
+    #[wasm_bindgen(catch)]
+    pub fn sanMove(&self, mv: &Move) -> Result<String, JsValue> {
+        if !self.0.is_move_legal(mv.cs()) {
+            return Err(js_sys::Error::new(&format!("Illegal move: {}", mv.cs())).into());
+        }
+        Ok(self.0.board().to_san(mv.cs()))
     }
 
     /// Convert this game to a PGN string, without more metadata.
-    /// The moves are translated to the long algebraic notation.
+    /// The moves are translated to the standard algebraic notation.
     pub fn toPgn(&self) -> String {
         self.0.to_pgn()
     }
 
+    /// The movetext up to a number of half-moves, going on past the
+    /// current board with the undone moves.
+    pub fn toPgnUpto(&self, ply: usize) -> String {
+        self.0.to_pgn_upto(ply)
+    }
+
+    /// Convert this game to a complete PGN, starting with the seven tag
+    /// roster. The `Result` tag follows the result of the game.
+    pub fn toPgnWithTags(&self, tags: &PGNTags) -> String {
+        self.0.to_pgn_with_tags(&tags.0)
+    }
+
 }
 
 
@@ -191,3 +250,116 @@ impl PGNTags {
         self.0.to_pgn()
     }
 }
+
+
+
+#[cfg(test)]
+mod game_test {
+    use wasm_bindgen_test::*;
+    use super::*;
+    use crate::units::PieceType;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_play_move_checked() {
+        let mut game = Game::new();
+        let e2 = Square::fromSan("e2").unwrap();
+        let e5 = Square::fromSan("e5").unwrap();
+        assert!(!game.playMoveChecked(&Move::quiet(&e2, &e5)));
+        assert!(game.board().equals(&Board::new()));
+        assert!(game.0.moves.is_empty());
+
+        let e4 = Square::fromSan("e4").unwrap();
+        assert!(game.playMoveChecked(&Move::quiet(&e2, &e4)));
+        assert_eq!(game.0.moves.len(), 1);
+        assert!(game.lastMove().unwrap().equals(&Move::quiet(&e2, &e4)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_san_history() {
+        let game = Game::fromPgn("1. e4 d5 2. exd5 Nf6 3. Bb5+ Nc6 4. Nf3 a6 5. O-O axb5").unwrap();
+        assert_eq!(game.sanHistory(), vec![
+            "e4", "d5", "exd5", "Nf6", "Bb5+", "Nc6", "Nf3", "a6", "O-O", "axb5"
+        ]);
+
+        let game = Game::fromPgn("1. f3 e5 2. g4 Qh4#").unwrap();
+        assert_eq!(game.sanHistory().last().unwrap(), "Qh4#");
+        let board = Board::fromFen("7k/4P3/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        let e7 = Square::fromSan("e7").unwrap();
+        let e8 = Square::fromSan("e8").unwrap();
+        let queen = PieceType::fromChar('Q').unwrap();
+        assert_eq!(board.toSan(&Move::promotion(&e7, &e8, &queen)), "e8=Q+");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_play_move_checked_finished() {
+        let mut game = Game::fromPgn("1. f3 e5 2. g4 Qh4#").unwrap();
+        assert!(game.isFinished());
+        for mv in game.0.board().legal_moves() {
+            assert!(!game.playMoveChecked(&Move::from_cs(mv)));
+        }
+        assert_eq!(game.0.moves.len(), 4);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_play_move_checked_promotion() {
+        // Only the pawn may promote, and a knight "promotion" must not panic.
+        let board = Board::fromFen("4k3/P7/8/8/8/8/8/4K1N1 w - - 0 1").unwrap();
+        let mut game = Game::fromBoard(&board);
+        let queen = PieceType::fromChar('Q').unwrap();
+        let g1 = Square::fromSan("g1").unwrap();
+        let f3 = Square::fromSan("f3").unwrap();
+        assert!(!game.playMoveChecked(&Move::promotion(&g1, &f3, &queen)));
+        assert!(game.0.moves.is_empty());
+        assert!(game.board().equals(&board));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_to_pgn_with_tags() {
+        let mut tags = PGNTags::new();
+        tags.addTag("Event", "Casual game".to_owned());
+        tags.addTag("ECO", "C20".to_owned());
+        let game = Game::fromPgn("1. f3 e5 2. g4 Qh4#").unwrap();
+        let pgn = game.toPgnWithTags(&tags);
+        let names: Vec<_> = pgn.lines()
+            .take(8)
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(names, vec![
+            "[Event", "[Site", "[Date", "[Round", "[White", "[Black", "[Result", "[ECO"
+        ]);
+        assert!(pgn.starts_with("[Event \"Casual game\"]\n"));
+        assert!(pgn.contains("[Result \"0-1\"]\n"));
+        assert!(pgn.ends_with("\n\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+
+        // The result is read back from the exported game.
+        let game = Game::fromPgn(&pgn).unwrap();
+        assert!(game.isFinished());
+        assert_eq!(PGNTags::fromPgn(&pgn).0["ECO"], "C20");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_san_move() {
+        let game = Game::fromPgn("1. e4 e5 2. Nf3 Nc6").unwrap();
+        let mv = game.parseMove("Bb5").unwrap();
+        assert_eq!(game.sanMove(&mv).unwrap(), "Bb5");
+        let game = Game::fromPgn("1. f3 e5 2. g4").unwrap();
+        let mv = game.parseMove("Qh4").unwrap();
+        assert_eq!(game.sanMove(&mv).unwrap(), "Qh4#");
+    }
+
+    // Errors are JS objects, which only exist in a WASM runtime.
+    #[wasm_bindgen_test]
+    fn test_parse_move_error() {
+        let game = Game::fromPgn("1. e4 e5").unwrap();
+        let message = |san: &str| -> String {
+            let err = game.parseMove(san).err().unwrap();
+            js_sys::Error::from(err).message().into()
+        };
+        assert!(message("Nz9").contains("Nz9"));
+        assert!(message("Ne5").contains("e5"));
+
+        let e4 = Square::fromSan("e4").unwrap();
+        let e5 = Square::fromSan("e5").unwrap();
+        assert!(game.sanMove(&Move::quiet(&e4, &e5)).is_err());
+    }
+}
@@ -18,12 +18,18 @@ mod engine;
 mod units;
 pub use units::{Color, PieceType};
 
+mod bit;
+pub use bit::Bitboard;
+
 mod moves;
 pub use moves::{Move, PGNMove, CastlingSide};
 
 mod position;
 pub use position::Board;
 
+mod builder;
+pub use builder::Builder;
+
 mod state;
 pub use state::{GameResult, WinType, DrawType};
 
@@ -31,4 +37,4 @@ mod game;
 pub use game::{Game, PGNTags};
 
 mod perft;
-pub use perft::perft;
\ No newline at end of file
+pub use perft::{perft, perftDivide};
\ No newline at end of file
@@ -18,4 +18,19 @@ fn explore(board: cs::Board, depth: u32) -> u32 {
 #[wasm_bindgen]
 pub fn perft(board: &Board, depth: u32) -> u32 {
     explore(board.0.clone(), depth)
+}
+
+/// The leaf nodes under each legal move of `board`, as an array of
+/// `{ move, nodes }` objects where `move` is the UCI string of the move.
+/// The counts are numbers, which stay exact far above 32 bits.
+#[wasm_bindgen]
+pub fn perftDivide(board: &Board, depth: u32) -> js_sys::Array {
+    cs::perft::divide(&board.0, depth).into_iter()
+        .map(|(mv, nodes)| {
+            let entry = js_sys::Object::new();
+            js_sys::Reflect::set(&entry, &"move".into(), &mv.uci().into()).unwrap();
+            js_sys::Reflect::set(&entry, &"nodes".into(), &(nodes as f64).into()).unwrap();
+            JsValue::from(entry)
+        })
+        .collect()
 }
\ No newline at end of file
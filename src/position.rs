@@ -3,6 +3,7 @@ use wasm_bindgen::prelude::*;
 use chess_std as cs;
 use crate::units::{Square, Color, PieceType, Piece};
 use crate::moves::Move;
+use crate::bit::Bitboard;
 
 
 /// A `Board` is a representation of the game that views, modifies the position.
@@ -25,6 +26,11 @@ impl Board {
         self.0 == other.0
     }
 
+    /// Whether both boards hold the same position, ignoring the move clocks.
+    pub fn samePosition(&self, other: &Board) -> bool {
+        self.0.same_position(&other.0)
+    }
+
     /// The initial configuration.
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
@@ -55,12 +61,27 @@ impl Board {
 
     /// The number of similar pieces on the board.
     pub fn countPiece(&self, pc: &Piece) -> u32 {
-        self.0.piece(pc.0).pop_count()
+        self.0.count(pc.0)
     }
 
     /// The number of pieces of a specific type on the board.
     pub fn countPieceType(&self, ptype: &PieceType) -> u32 {
-        self.0.piece_type(ptype.0).pop_count()
+        self.0.count_type(ptype.0)
+    }
+
+    /// Whether at least one such piece is on the board.
+    pub fn hasPiece(&self, pc: &Piece) -> bool {
+        self.0.has_piece(pc.0)
+    }
+
+    /// The squares of the similar pieces on the board.
+    pub fn pieceBitboard(&self, pc: &Piece) -> Bitboard {
+        Bitboard(self.0.piece_type(pc.0.ptype) & self.0.color(pc.0.color))
+    }
+
+    /// The squares of the pieces of a player.
+    pub fn colorBitboard(&self, col: &Color) -> Bitboard {
+        Bitboard(self.0.color(col.0))
     }
 
     /// The number of pieces of a player.
@@ -109,6 +130,17 @@ impl Board {
         self.0.is_attacked(sq.cs(), by.0)
     }
 
+    /// The number of pieces of a color that attack a square.
+    pub fn numAttackers(&self, sq: &Square, by: &Color) -> u32 {
+        self.0.num_attackers(sq.cs(), by.0)
+    }
+
+    /// Whether a square is attacked by at least a piece of a color,
+    /// which defends the piece standing there.
+    pub fn isDefended(&self, sq: &Square, by: &Color) -> bool {
+        self.0.is_defended(sq.cs(), by.0)
+    }
+
     /// Find the king on the board, assuming the position is legal.
     pub fn kingSquareOf(&self, player: &Color) -> Square {
         Square::from_cs(self.0.king_square_of(player.0))
@@ -118,6 +150,16 @@ impl Board {
         self.0.is_king_checked()
     }
 
+    /// The number of pieces checking the current king, at most 2.
+    pub fn checkCount(&self) -> u32 {
+        self.0.check_count()
+    }
+
+    /// Whether the current king is checked by two pieces at once.
+    pub fn isDoubleCheck(&self) -> bool {
+        self.0.is_double_check()
+    }
+
     /// The selected piece of a move.
     pub fn movedBy(&self, mv: &Move) -> Piece {
         Piece(self.0.moved_by(mv.cs()))
@@ -128,9 +170,43 @@ impl Board {
         self.0.captured_by(mv.cs()).map(Piece)
     }
 
-    /// Apply the move in place.
-    pub fn applyMove(&mut self, mv: &Move) {
-        self.0.apply_move(mv.cs());
+    /// Apply the move in place. Throws if the move is illegal,
+    /// in which case the board is left untouched.
+    #[wasm_bindgen(catch)]
+    pub fn applyMove(&mut self, mv: &Move) -> Result<(), JsValue> {
+        self.0.try_apply_move(mv.cs())
+            .map_err(|err| js_sys::Error::new(&err).into())
+    }
+
+    /// Put a piece on an empty square. Throws if the square is occupied.
+    ///
+    /// Editing may go through invalid positions (e.g. without kings),
+    /// so check `isValid()` before playing from the edited board:
+    ///
+    /// ```js
+    /// board.place(piece, square);
+    /// if (board.isValid()) { game = Game.fromBoard(board); }
+    /// ```
+    #[wasm_bindgen(catch)]
+    pub fn place(&mut self, pc: &Piece, sq: &Square) -> Result<(), JsValue> {
+        self.0.place(pc.0, sq.cs())
+            .map_err(|err| js_sys::Error::new(&err).into())
+    }
+
+    /// Remove the piece at a square, if any. See `place` about validity.
+    pub fn clear(&mut self, sq: &Square) {
+        self.0.clear(sq.cs());
+    }
+
+    /// Change the side to move. See `place` about validity.
+    pub fn setTurn(&mut self, col: &Color) {
+        self.0.set_turn(col.0);
+    }
+
+    /// A copy of this board with another side to move,
+    /// e.g. to look for the threats of the opponent.
+    pub fn withTurn(&self, col: &Color) -> Board {
+        Board(self.0.with_turn(col.0))
     }
 
     /// Whether this position may theoretically occur.
@@ -138,7 +214,7 @@ impl Board {
         self.0.is_valid()
     }
 
-    /// A unique hash.
+    /// A unique hash, which ignores the move clocks.
     pub fn zobristHash(&self) -> u64 {
         self.0.zobrist_hash()
     }
@@ -148,6 +224,11 @@ impl Board {
         self.0.to_unicode()
     }
 
+    /// Return a 'pretty' Unicode board representation, as seen by a player.
+    pub fn toUnicodeStrFrom(&self, from: &Color) -> String {
+        self.0.to_unicode_oriented(from.0)
+    }
+
     pub fn toString(&self) -> String {
         format!("{:?}", self.0)
     }
@@ -10,9 +10,14 @@ use crate::position::Board;
 #[wasm_bindgen]
 impl Board {
     
-    /// Get the half move clock.
+    /// The number of half-moves since the last capture or pawn move.
     pub fn halfMoveClock(&self) -> u32 {
-        self.0.half_move_clock
+        self.0.halfmove_clock()
+    }
+
+    /// The number of the move pair being played, starting at 1.
+    pub fn fullmoveNumber(&self) -> u32 {
+        self.0.fullmove_number()
     }
 
     /// Returns the number of moves played since the beginning of the game.
@@ -30,6 +35,11 @@ impl Board {
         moves::gen_into_array(self.0.legal_moves_from(sq.cs()))
     }
 
+    /// Returns the legal moves that land on a square, using cache.
+    pub fn legalMovesTo(&self, sq: &Square) -> js_sys::Array {
+        moves::gen_into_array(self.0.legal_moves_to(sq.cs()))
+    }
+
     /// Returns the legal moves which are captures, using cache.
     pub fn legalCaptures(&self) -> js_sys::Array {
         moves::gen_into_array(self.0.legal_captures())
@@ -67,6 +77,12 @@ impl Board {
         PGNMove(self.0.pgn_move(mv.cs()))
     }
 
+    /// The standard algebraic notation (SAN) of a legal move,
+    /// with the check or checkmate suffix.
+    pub fn toSan(&self, mv: &Move) -> String {
+        self.0.to_san(mv.cs())
+    }
+
     /// If the current player's king is checked.
     pub fn inCheck(&self) -> bool {
         self.0.in_check()
@@ -22,6 +22,14 @@ impl Color {
         *self == *rhs
     }
 
+    pub fn white() -> Color {
+        Self(cs::Color::White)
+    }
+
+    pub fn black() -> Color {
+        Self(cs::Color::Black)
+    }
+
     #[wasm_bindgen(getter)]
     pub fn opponent(&self) -> Color {
         Self(self.0.opponent())
@@ -56,6 +64,37 @@ impl PieceType {
         *self == *rhs
     }
 
+    pub fn pawn() -> PieceType {
+        Self(cs::PieceType::Pawn)
+    }
+
+    pub fn knight() -> PieceType {
+        Self(cs::PieceType::Knight)
+    }
+
+    pub fn bishop() -> PieceType {
+        Self(cs::PieceType::Bishop)
+    }
+
+    pub fn rook() -> PieceType {
+        Self(cs::PieceType::Rook)
+    }
+
+    pub fn queen() -> PieceType {
+        Self(cs::PieceType::Queen)
+    }
+
+    pub fn king() -> PieceType {
+        Self(cs::PieceType::King)
+    }
+
+    /// All the piece types, from the pawn to the king.
+    pub fn all() -> js_sys::Array {
+        cs::prelude::ALL_PIECE_TYPES.iter()
+            .map(|&ptype| JsValue::from(Self(ptype)))
+            .collect()
+    }
+
     /// The relative piece value.
     #[wasm_bindgen(getter)]
     pub fn value(&self) -> u8 {
@@ -161,10 +200,13 @@ impl Square {
         *self == *rhs
     }
 
-    #[wasm_bindgen(constructor)]
-    pub fn new(rank: u8, file: u8) -> Self {
-        let sq = cs::Square::new(cs::Rank::from(rank), cs::File::from(file));
-        Self(sq.into())
+    /// A square from a rank and a file, each from 0 to 7.
+    #[wasm_bindgen(constructor, catch)]
+    pub fn new(rank: u8, file: u8) -> Result<Square, JsValue> {
+        match (cs::Rank::new(rank), cs::File::new(file)) {
+            (Some(r), Some(f)) => Ok(Self::from_cs(cs::Square::new(r, f))),
+            _ => Err(js_sys::Error::new("Rank and file must be from 0 to 7").into())
+        }
     }
 
     pub fn fromScalar(i: u8) -> Self {
@@ -206,4 +248,54 @@ impl Square {
             .map(Self::from_cs)
             .map_err(|_| js_sys::Error::new("Couldn't parse SAN").into())
     }
+
+    /// Assuming this square is in White's perspective, this returns
+    /// a vertically flipped square for Black, and is a no-op for White.
+    pub fn relative(&self, player: &Color) -> Square {
+        Self::from_cs(self.cs().relative(player.0))
+    }
+
+    /// This swaps the view of the players.
+    pub fn flipVertical(&self) -> Square {
+        Self::from_cs(self.cs().flip_vertical())
+    }
+
+    /// Mirror the square from the queen side to the king side, and conversely.
+    pub fn mirrorHorizontal(&self) -> Square {
+        Self::from_cs(self.cs().mirror_horizontal())
+    }
+
+    /// Rotate the square around the center of the board.
+    pub fn rotate180(&self) -> Square {
+        Self::from_cs(self.cs().rotate180())
+    }
+}
+
+
+#[cfg(test)]
+mod units_test {
+    use wasm_bindgen_test::*;
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_square_views() {
+        let f3 = Square::fromSan("f3").unwrap();
+        assert!(f3.flipVertical().equals(&Square::fromSan("f6").unwrap()));
+        assert!(f3.mirrorHorizontal().equals(&Square::fromSan("c3").unwrap()));
+        assert!(f3.rotate180().equals(&Square::fromSan("c6").unwrap()));
+        assert!(f3.relative(&Color(cs::Color::White)).equals(&f3));
+        assert!(f3.relative(&Color(cs::Color::Black)).equals(&f3.flipVertical()));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_named_constructors() {
+        assert_eq!(Color::white().toString(), 'w');
+        assert_eq!(Color::black().toString(), 'b');
+        assert_eq!(PieceType::pawn().toString(), 'P');
+        assert_eq!(PieceType::knight().toString(), 'N');
+        assert_eq!(PieceType::bishop().toString(), 'B');
+        assert_eq!(PieceType::rook().toString(), 'R');
+        assert_eq!(PieceType::queen().toString(), 'Q');
+        assert_eq!(PieceType::king().toString(), 'K');
+    }
 }